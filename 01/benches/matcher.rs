@@ -0,0 +1,88 @@
+//! Throughput of the day 1 matcher against the regex approach it replaced,
+//! which compiled two regexes for every line. Run with `cargo bench -p
+//! aoc-2023-12-01`; pass a size in MB to change the input size.
+//!
+//! The old way is so slow that it only gets a sample of the input.
+
+use std::env;
+use std::time::Instant;
+
+use aoc_common::Rng;
+use aoc_2023_12_01::{find_calibration_values, DigitVocabulary, Matcher, Mode};
+use regex::Regex;
+
+const NUMS : [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const RENUMS : &str = "(one|two|three|four|five|six|seven|eight|nine|\\d)";
+const LEGACY_SAMPLE : usize = 64 * 1024;
+
+fn main() {
+    let megabytes = env::args()
+        .skip(1)
+        .find_map(|a| a.parse::<usize>().ok())
+        .unwrap_or(16);
+    let input = generate(megabytes * 1024 * 1024);
+
+    let matcher = Matcher::new(Mode::DigitsAndWords, &DigitVocabulary::english());
+    let value = |l: &str| find_calibration_values(l, &matcher).unwrap();
+    measure("automaton", &input, value);
+
+    let sample = &input[..=input[..LEGACY_SAMPLE.min(input.len())].rfind('\n').unwrap()];
+    let old = measure("per-line regex", sample, legacy_calibration_value);
+
+    assert_eq!(sample.lines().map(value).sum::<i64>(), old,
+        "Both implementations agree");
+}
+
+fn measure(name: &str, input: &str, f: impl Fn(&str) -> i64) -> i64 {
+    let start = Instant::now();
+    let total = input.lines().map(f).sum::<i64>();
+    let secs = start.elapsed().as_secs_f64();
+
+    println!("{:>16}: {:>10.2} MB/s ({:.3}s)",
+        name, input.len() as f64 / (1024.0 * 1024.0) / secs, secs);
+
+    total
+}
+
+/// Lines like the puzzle's: letters, the odd digit, and plenty of number
+/// words, some of them overlapping.
+fn generate(size: usize) -> String {
+    let mut rng = Rng::new(0x2023_1201);
+    let mut next = |n: usize| rng.below(n);
+
+    let mut out = String::with_capacity(size + 64);
+    while out.len() < size {
+        for _ in 0..(4 + next(8)) {
+            match next(4) {
+                0 => out.push(char::from(b'1' + next(9) as u8)),
+                1 => out.push_str(NUMS[next(9)]),
+                _ => out.push(char::from(b'a' + next(26) as u8)),
+            }
+        }
+        out.push(char::from(b'1' + next(9) as u8));
+        out.push('\n');
+    }
+
+    out
+}
+
+fn legacy_calibration_value(line: &str) -> i64 {
+    let re_num1 = Regex::new(RENUMS).unwrap();
+    let re_num2 = Regex::new(format!(".*{}", RENUMS).as_str()).unwrap();
+
+    let (_, [num1]) = re_num1.captures(line).unwrap().extract();
+    let digit1 = legacy_str_to_i64(num1);
+    let digit2 = match re_num2.captures(line) {
+        Some(res) => legacy_str_to_i64(res.extract::<1>().1[0]),
+        None => digit1,
+    };
+
+    digit1 * 10 + digit2
+}
+
+fn legacy_str_to_i64(string: &str) -> i64 {
+    match NUMS.iter().position(|x| x == &string) {
+        Some(idx) => (idx + 1) as i64,
+        None => string.parse::<i64>().unwrap(),
+    }
+}
@@ -0,0 +1,120 @@
+//! How each line's calibration value was arrived at, for `--explain`.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::{find_calibration_values, Matcher, Token, TokenKind};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per line of input.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = aoc_common::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(aoc_common::Error::Usage(
+                format!("--format must be text or json, not {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation {
+    pub line_number: usize,
+    pub line: String,
+    pub value: Option<i64>,
+    pub first: Option<ExplainedToken>,
+    pub last: Option<ExplainedToken>,
+}
+
+/// A [`Token`] along with the text it matched.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExplainedToken {
+    pub text: String,
+    pub value: i64,
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+impl ExplainedToken {
+    fn new(line: &str, token: Token) -> Self {
+        ExplainedToken {
+            text: line[token.start..token.end].to_string(),
+            value: token.value,
+            start: token.start,
+            end: token.end,
+            kind: token.kind,
+        }
+    }
+}
+
+impl Explanation {
+    /// `line_number` counts from 1.
+    pub fn new(line_number: usize, line: &str, matcher: &Matcher) -> Self {
+        Explanation {
+            line_number,
+            line: line.to_string(),
+            value: find_calibration_values(line, matcher),
+            first: matcher.first(line).map(|t| ExplainedToken::new(line, t)),
+            last: matcher.last(line).map(|t| ExplainedToken::new(line, t)),
+        }
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Json => serde_json::to_string(self).unwrap(),
+            Format::Text => {
+                let describe = |which: &str, t: &ExplainedToken| format!(
+                    "{} {:?} ({}) at {}..{}", which, t.text,
+                    match t.kind {
+                        TokenKind::Digit => "digit",
+                        TokenKind::Word => "word",
+                    },
+                    t.start, t.end);
+
+                match (&self.value, &self.first, &self.last) {
+                    (Some(value), Some(first), Some(last)) => format!(
+                        "{}: {} = {}: {}, {}", self.line_number, self.line, value,
+                        describe("first", first), describe("last", last)),
+                    _ => format!("{}: {} has no digit", self.line_number, self.line),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DigitVocabulary, Mode};
+
+    #[test]
+    fn text_and_json() {
+        let matcher = Matcher::new(Mode::DigitsAndWords, &DigitVocabulary::english());
+        let lines = [
+            Explanation::new(1, "xtwone3", &matcher),
+            Explanation::new(2, "nope", &matcher),
+        ];
+
+        assert_eq!(lines[0].render(Format::Text),
+            r#"1: xtwone3 = 23: first "two" (word) at 1..4, last "3" (digit) at 6..7"#);
+        assert_eq!(lines[1].render(Format::Text), "2: nope has no digit");
+
+        assert_eq!(lines[0].render(Format::Json), concat!(
+            r#"{"line_number":1,"line":"xtwone3","value":23,"#,
+            r#""first":{"text":"two","value":2,"start":1,"end":4,"kind":"word"},"#,
+            r#""last":{"text":"3","value":3,"start":6,"end":7,"kind":"digit"}}"#));
+        assert_eq!(lines[1].render(Format::Json),
+            r#"{"line_number":2,"line":"nope","value":null,"first":null,"last":null}"#);
+    }
+}
@@ -0,0 +1,504 @@
+use std::fmt;
+use std::str::FromStr;
+
+use aoc_common::{Answers, Input, Part, Solver};
+use aho_corasick::{AhoCorasick, MatchKind};
+
+use serde::Serialize;
+
+mod explain;
+mod vocab;
+
+pub use explain::{ExplainedToken, Explanation, Format};
+pub use vocab::DigitVocabulary;
+
+/// Which tokens count as a digit when looking for the calibration value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Part 1: only the digits themselves.
+    Digits,
+    /// Part 2: the digits, or the digits spelled out as words.
+    DigitsAndWords,
+}
+
+/// What to do with a line that has no digit on it at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OnInvalid {
+    /// Give up on the part the line is bad for. The other part carries on.
+    #[default]
+    Error,
+    /// Leave the line out of the total.
+    Skip,
+    /// Count the line as 0.
+    Zero,
+}
+
+impl FromStr for OnInvalid {
+    type Err = aoc_common::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(OnInvalid::Error),
+            "skip" => Ok(OnInvalid::Skip),
+            "zero" => Ok(OnInvalid::Zero),
+            _ => Err(aoc_common::Error::Usage(
+                format!("--on-invalid must be error, skip or zero, not {:?}", s))),
+        }
+    }
+}
+
+/// A line with no calibration value in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationError {
+    /// Counting from 1, like an editor does.
+    pub line_number: usize,
+    pub line: String,
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: no digit in {:?}", self.line_number, self.line)
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+impl From<CalibrationError> for aoc_common::Error {
+    fn from(e: CalibrationError) -> Self {
+        aoc_common::Error::Input(e.to_string())
+    }
+}
+
+/// The sum of the calibration values, and the lines that didn't have one if
+/// they weren't fatal.
+#[derive(Debug, Default, PartialEq)]
+pub struct Calibration {
+    pub total: i64,
+    pub rejected: Vec<CalibrationError>,
+}
+
+#[derive(Debug, Default)]
+pub struct Day01 {
+    pub vocab: DigitVocabulary,
+    pub on_invalid: OnInvalid,
+}
+
+impl Day01 {
+    pub fn calibrator(&self) -> Calibrator {
+        let part = |mode| (Matcher::new(mode, &self.vocab), Ok(Default::default()));
+
+        Calibrator {
+            on_invalid: self.on_invalid,
+            parts: [part(Mode::Digits), part(Mode::DigitsAndWords)],
+            line_number: 0,
+        }
+    }
+
+    /// Both parts' calibrations for the whole input. Only failing to read it
+    /// at all is fatal to both.
+    pub fn calibrate(&self, input: Input)
+        -> Result<[Result<Calibration, CalibrationError>; 2], aoc_common::Error>
+    {
+        let mut calibrator = self.calibrator();
+
+        for line in input.lines() {
+            calibrator.add(&line?);
+        }
+
+        Ok(calibrator.finish())
+    }
+}
+
+impl Solver for Day01 {
+    fn day(&self) -> u32 {
+        1
+    }
+
+    /// A part whose input is bad goes unanswered, with a note saying why,
+    /// but the other part is still answered. Part 2's example has no digits
+    /// on some lines, for instance.
+    fn solve(&self, input: Input) -> Result<Answers, aoc_common::Error> {
+        let [part1, part2] = match self.calibrate(input)? {
+            [Err(e), Err(_)] => return Err(e.into()),
+            parts => parts,
+        };
+
+        let mut notes = vec![];
+        let mut answer = |part, calibration: Result<Calibration, CalibrationError>|
+            calibration
+                .map_err(|e| notes.push((part, e.to_string())))
+                .ok()
+                .map(|c| c.total.into());
+
+        Ok(Answers {
+            part1: answer(Part::One, part1),
+            part2: answer(Part::Two, part2),
+            notes,
+        })
+    }
+}
+
+/// Adds up both parts' calibration values a line at a time, so the input
+/// never has to be in memory all at once.
+///
+/// The parts are kept apart: a line that's fatal to one of them stops only
+/// that one.
+pub struct Calibrator {
+    on_invalid: OnInvalid,
+    parts: [(Matcher, Result<Calibration, CalibrationError>); 2],
+    line_number: usize,
+}
+
+impl Calibrator {
+    pub fn add(&mut self, line: &str) {
+        self.line_number += 1;
+
+        for (matcher, result) in &mut self.parts {
+            // This part has already given up
+            let Ok(calibration) = result
+            else {
+                continue;
+            };
+
+            match find_calibration_values(line, matcher) {
+                Some(value) => calibration.total += value,
+                None => {
+                    let e = CalibrationError {
+                        line_number: self.line_number,
+                        line: line.to_string(),
+                    };
+
+                    // Zero adds nothing to the total, so it's the same as
+                    // skipping apart from the name.
+                    match self.on_invalid {
+                        OnInvalid::Error => *result = Err(e),
+                        OnInvalid::Skip | OnInvalid::Zero =>
+                            calibration.rejected.push(e),
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> [Result<Calibration, CalibrationError>; 2] {
+        self.parts.map(|(_, result)| result)
+    }
+}
+
+/// Finds the first and last digit tokens on a line. It's built once from the
+/// vocabulary and then used for every line.
+///
+/// There are two automata over the same tokens: one reads the line forwards
+/// and stops at the first token, and the other has every token spelled
+/// backwards and reads the line from the end. Neither looks at any more of
+/// the line than it has to.
+///
+/// Reading from the end means reversing the end of the line first. That's
+/// done on the stack, a window at a time, so there's nothing to allocate
+/// unless the last token is a long way from the end.
+#[derive(Debug)]
+pub struct Matcher {
+    forward: AhoCorasick,
+    backward: AhoCorasick,
+    // The digit each token stands for, by pattern ID in both automata
+    values: Vec<i64>,
+    // The longest token, in bytes
+    longest: usize,
+}
+
+// How much of the end of a line to reverse on the stack. Puzzle lines fit
+// with room to spare.
+const WINDOW : usize = 128;
+
+impl Matcher {
+    pub fn new(mode: Mode, vocab: &DigitVocabulary) -> Self {
+        let words = match mode {
+            Mode::Digits => DigitVocabulary::empty(),
+            Mode::DigitsAndWords => vocab.clone(),
+        };
+
+        let tokens : Vec<(String, i64)> = (0..=9)
+            .map(|n| (n.to_string(), n))
+            .chain(words.words().map(|(w, n)| (w.to_string(), n)))
+            .collect();
+
+        // Leftmost-longest, so that where two tokens start in the same place
+        // (VIII and V, say) the longer one wins.
+        let build = |patterns: Vec<Vec<u8>>| AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(patterns)
+            .unwrap();
+
+        Matcher {
+            forward: build(tokens.iter()
+                .map(|(t, _)| t.as_bytes().to_vec())
+                .collect()),
+            backward: build(tokens.iter()
+                .map(|(t, _)| t.bytes().rev().collect())
+                .collect()),
+            values: tokens.iter().map(|(_, n)| *n).collect(),
+            longest: tokens.iter().map(|(t, _)| t.len()).max().unwrap_or(0),
+        }
+    }
+
+    pub fn first(&self, line: &str) -> Option<Token> {
+        self.forward.find(line)
+            .map(|m| self.token(m.pattern().as_usize(), m.start(), m.end()))
+    }
+
+    pub fn last(&self, line: &str) -> Option<Token> {
+        // Reversing the bytes rather than the chars means the line may not be
+        // valid UTF-8 any more, but the patterns were reversed the same way.
+        let mut window = [0u8; WINDOW];
+        let n = line.len().min(WINDOW);
+        for (w, b) in window.iter_mut().zip(line.bytes().rev()) {
+            *w = b;
+        }
+
+        // A token that starts far enough into the window can't have a longer
+        // one sharing its end, or one further right, cut off by the edge
+        let found = self.backward.find(&window[..n]);
+        let m = match found {
+            Some(m) if n == line.len() || m.start() + self.longest <= n => found,
+            None if n == line.len() => None,
+            _ => self.backward.find(&line.bytes().rev().collect::<Vec<u8>>()),
+        };
+
+        m.map(|m| self.token(m.pattern().as_usize(),
+            line.len() - m.end(), line.len() - m.start()))
+    }
+
+    fn token(&self, pattern: usize, start: usize, end: usize) -> Token {
+        Token {
+            value: self.values[pattern],
+            start,
+            end,
+            // The ten digits are the first ten patterns
+            kind: if pattern < 10 { TokenKind::Digit } else { TokenKind::Word },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKind {
+    Digit,
+    Word,
+}
+
+/// One digit found on a line. `start` and `end` are byte offsets into the
+/// line, with `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token {
+    pub value: i64,
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+/// The calibration value is the first digit on the line followed by the last.
+///
+/// The first digit is the leftmost token, and the last is the rightmost, as if
+/// the line were read backwards. The two are found independently, so they may
+/// overlap: "eightwo" is 82 and "oneight" is 18. Rewriting words to digits
+/// left to right and then looking for digits would get those wrong, because
+/// rewriting "eight" spoils the "two" that shares its "t".
+///
+/// A line with no digits has no calibration value.
+pub fn find_calibration_values(line: &str, matcher: &Matcher) -> Option<i64> {
+    let digit1 = matcher.first(line)?.value;
+    let digit2 = matcher.last(line).map_or(digit1, |t| t.value);
+
+    Some(digit1 * 10 + digit2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(line: &str, mode: Mode) -> i64 {
+        find_calibration_values(line,
+            &Matcher::new(mode, &DigitVocabulary::english())).unwrap()
+    }
+
+    fn value_in(line: &str, vocab: &str) -> i64 {
+        find_calibration_values(line,
+            &Matcher::new(Mode::DigitsAndWords, &DigitVocabulary::parse(vocab).unwrap()))
+            .unwrap()
+    }
+
+    #[test]
+    fn digits_only() {
+        let lines = ["1abc2", "pqr3stu8vwx", "a1b2c3d4e5f", "treb7uchet"];
+        let values : Vec<_> = lines.iter()
+            .map(|l| value(l, Mode::Digits))
+            .collect();
+
+        assert_eq!(values, [12, 38, 15, 77], "Words are not digits here");
+        assert_eq!(value("two1nine", Mode::Digits), 11,
+            "A lone digit is both first and last");
+    }
+
+    #[test]
+    fn digits_and_words() {
+        let lines = ["two1nine", "eightwothree", "abcone2threexyz", "xtwone3four",
+            "4nineeightseven2", "zoneight234", "7pqrstsixteen"];
+        let values : Vec<_> = lines.iter()
+            .map(|l| value(l, Mode::DigitsAndWords))
+            .collect();
+
+        assert_eq!(values, [29, 83, 13, 24, 42, 14, 76]);
+    }
+
+    #[test]
+    fn overlapping_words() {
+        // Every pair of words where the end of one is the start of another,
+        // plus chains and noise around them. Each line is (input, expected).
+        let cases = [
+            ("eightwo", 82),
+            ("oneight", 18),
+            ("twone", 21),
+            ("threeight", 38),
+            ("fiveight", 58),
+            ("sevenine", 79),
+            ("nineight", 98),
+            ("eighthree", 83),
+            ("eightwone", 81),
+            ("oneightwo", 12),
+            ("twoneight", 28),
+            ("sevenineight", 78),
+            ("xtwonex", 21),
+            ("eeightwoo", 82),
+            ("1oneight", 18),
+            ("eightwo9", 89),
+            ("3twone", 31),
+            ("oneeight", 18),
+            ("nine", 99),
+            ("twone1twone", 21),
+        ];
+
+        for (line, expected) in cases {
+            assert_eq!(value(line, Mode::DigitsAndWords),
+                expected, "{} is {}", line, expected);
+        }
+    }
+
+    #[test]
+    fn overlaps_do_not_leak_into_digits_mode() {
+        assert_eq!(value("oneight7twone", Mode::Digits), 77,
+            "Words are ignored even when they overlap");
+        assert_eq!(value("8eightwo2", Mode::Digits), 82);
+    }
+
+    #[test]
+    fn other_languages() {
+        let french = include_str!("../vocab/french.txt");
+        let german = include_str!("../vocab/german.txt");
+
+        assert_eq!(value_in("xxunxdeuxtroisx", french), 13);
+        assert_eq!(value_in("huitrois", french), 83, "Overlaps still count");
+        assert_eq!(value_in("onetwo7", french), 77, "English is not French");
+        assert_eq!(value_in("fünfundzweiß", german), 52);
+        assert_eq!(value_in("fuenfneun", german), 59);
+        assert_eq!(value_in("zwei", german), 22);
+    }
+
+    #[test]
+    fn roman_numerals() {
+        let roman = include_str!("../vocab/roman.txt");
+
+        assert_eq!(value_in("VIII", roman), 88, "Longest numeral wins both ways");
+        assert_eq!(value_in("xIVxVIIx", roman), 47);
+        assert_eq!(value_in("IX2", roman), 92);
+        assert_eq!(value_in("3vi", roman), 33, "Numerals are upper case");
+    }
+
+    #[test]
+    fn long_lines() {
+        let roman = include_str!("../vocab/roman.txt");
+        let padding = "x".repeat(WINDOW - 3);
+
+        assert_eq!(value_in(&format!("VIII{}", padding), roman), 88,
+            "Only III fits in the window, but VIII is the last numeral");
+        assert_eq!(value(&format!("7{}", "x".repeat(10 * WINDOW)), Mode::Digits), 77,
+            "Nothing in the window at all");
+        assert_eq!(value(&format!("1{}two", "x".repeat(10 * WINDOW)), Mode::DigitsAndWords), 12);
+    }
+
+    #[test]
+    fn token_positions() {
+        let matcher = Matcher::new(Mode::DigitsAndWords, &DigitVocabulary::english());
+        let line = "xeightwo7twone";
+
+        assert_eq!(matcher.first(line),
+            Some(Token { value: 8, start: 1, end: 6, kind: TokenKind::Word }));
+        assert_eq!(matcher.last(line),
+            Some(Token { value: 1, start: 11, end: 14, kind: TokenKind::Word }));
+        assert_eq!(matcher.last("one7x"),
+            Some(Token { value: 7, start: 3, end: 4, kind: TokenKind::Digit }));
+    }
+
+    #[test]
+    fn no_digits() {
+        let matcher = Matcher::new(Mode::Digits, &DigitVocabulary::english());
+        assert_eq!(find_calibration_values("eightwo", &matcher), None);
+        assert_eq!(find_calibration_values("", &matcher), None);
+    }
+
+    #[test]
+    fn invalid_line_policies() {
+        let input = "1abc2\nnothing\ntwo3four\n\n";
+        let calibrate = |on_invalid| {
+            let mut c = Day01 { on_invalid, ..Default::default() }.calibrator();
+            for line in input.lines() {
+                c.add(line);
+            }
+            c.finish()
+        };
+
+        let nothing = CalibrationError { line_number: 2, line: String::from("nothing") };
+        assert_eq!(calibrate(OnInvalid::Error), [Err(nothing.clone()), Err(nothing)],
+            "Error stops at the first bad line");
+
+        for policy in [OnInvalid::Skip, OnInvalid::Zero] {
+            let [part1, part2] = calibrate(policy).map(Result::unwrap);
+            assert_eq!(part1.total, 12 + 33, "Bad lines add nothing");
+            assert_eq!(part1.rejected.iter().map(|e| e.line_number).collect::<Vec<_>>(),
+                [2, 4], "Every bad line is reported");
+            assert_eq!(part2.rejected.len(), 2, "No words in line 2 either");
+        }
+    }
+
+    #[test]
+    fn both_parts_in_one_run() {
+        let input = "1abc2\ntwo3four\n";
+        let answers = Day01::default().solve(input.into()).unwrap();
+
+        assert_eq!(answers, Answers::new(12i64 + 33, 12i64 + 24));
+    }
+
+    #[test]
+    fn one_part_failing_leaves_the_other() {
+        let mut c = Day01::default().calibrator();
+        for line in ["eightwothree", "1abc2"] {
+            c.add(line);
+        }
+        let [part1, part2] = c.finish();
+
+        assert_eq!(part1.unwrap_err().line_number, 1, "No plain digit for part 1");
+        assert_eq!(part2.unwrap().total, 83 + 12, "Part 2 reads past it");
+    }
+
+    #[test]
+    fn part2_sample() {
+        let answers = Day01::default().solve(include_str!("../smallinput.txt").into()).unwrap();
+
+        assert_eq!(answers, Answers {
+            part1: None,
+            part2: Some(281i64.into()),
+            notes: vec![(Part::One, String::from(r#"line 2: no digit in "eightwothree""#))],
+        }, "Part 1 has nothing to say about lines like eightwothree");
+        assert!(Day01::default().solve("nothing\n".into()).is_err(),
+            "It's only an error if neither part has an answer");
+    }
+}
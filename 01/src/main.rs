@@ -1,47 +1,76 @@
-use std::env;
-use std::fs::read_to_string;
-use regex::Regex;
-
-const NUMS : [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
-const RENUMS : &str = "(one|two|three|four|five|six|seven|eight|nine|\\d)";
+use aoc_common::{Args, Error, Part};
+use aoc_2023_12_01::{Calibration, Day01, DigitVocabulary, Explanation, Format, Matcher, Mode, OnInvalid};
 
 fn main() {
-    let argv : Vec<_> = env::args().collect();
-    let total = read_to_string(&argv[1])
-        .unwrap()
-        .lines()
-        .map(|l| find_calibration_values(l))
-        .sum::<i64>();
-
-    println!("{}", total);
-}
+    aoc_common::run(|| {
+        let mut args = Args::from_env();
+        let vocab = match args.value("--vocab")? {
+            Some(path) => DigitVocabulary::load(&path)?,
+            None => DigitVocabulary::english(),
+        };
+        let on_invalid = match args.value("--on-invalid")? {
+            Some(policy) => policy.parse()?,
+            None => Default::default(),
+        };
+        let explaining = args.flag("--explain");
+        let format : Option<Format> = args.value("--format")?
+            .map(|f| f.parse()).transpose()?;
+        let part : Option<Part> = args.value("--part")?
+            .map(|p| p.parse()).transpose()?;
 
-fn str_to_i64(string: &str) -> i64 {
-    if let Some(idx) = NUMS.iter().position(|x| x == &string) {
-        return (idx + 1) as i64;
-    }
+        if !explaining && (format.is_some() || part.is_some()) {
+            return Err(Error::Usage(String::from("--format and --part go with --explain")));
+        }
 
-    return string.parse::<i64>().unwrap();
-}
+        let input = args.input()?;
+        let day = Day01 { vocab, on_invalid };
 
-fn find_calibration_values(line: &str) -> i64 {
-    let re_num1 = Regex::new(RENUMS).unwrap();
-    let re_num2 = Regex::new(format!(".*{}", RENUMS).as_str()).unwrap();
+        if explaining {
+            // Part 2 is the interesting one, so it's the default
+            let mode = match part {
+                Some(Part::One) => Mode::Digits,
+                _ => Mode::DigitsAndWords,
+            };
+            let matcher = Matcher::new(mode, &day.vocab);
 
-    let (digit1, digit2) : (i64, i64);
+            for (i, line) in input.lines().enumerate() {
+                let explanation = Explanation::new(i + 1, &line?, &matcher);
+                println!("{}", explanation.render(format.unwrap_or_default()));
+            }
+            return Ok(());
+        }
 
-    let res = re_num1.captures(line).unwrap();
-    let ( _, [num1] ) = res.extract();
+        // Each part stands alone, so one that fails doesn't take the other
+        // with it, unless neither has an answer
+        let parts = day.calibrate(input)?;
+        if let [Err(e), Err(_)] = parts {
+            return Err(e.into());
+        }
 
-    digit1 = str_to_i64(num1);
+        for (part, calibration) in Part::BOTH.into_iter().zip(parts) {
+            match calibration {
+                Ok(c) => {
+                    aoc_common::print_answer(&part.to_string(), c.total);
+                    report_rejected(part, &c, day.on_invalid);
+                }
+                Err(e) => eprintln!("{}: {}", part, e),
+            }
+        }
+        Ok(())
+    });
+}
 
-    if let Some(res) = re_num2.captures(line) {
-        let (_, [num]) = res.extract();
-        digit2 = str_to_i64(num);
-    }
-    else {
-        digit2 = digit1;
+fn report_rejected(part: Part, calibration: &Calibration, on_invalid: OnInvalid) {
+    if calibration.rejected.is_empty() {
+        return;
     }
 
-    return digit1 * 10 + digit2;
+    let what = match on_invalid {
+        OnInvalid::Zero => "counted as 0",
+        _ => "skipped",
+    };
+    eprintln!("{}: {} line(s) {}", part, calibration.rejected.len(), what);
+    for e in &calibration.rejected {
+        eprintln!("  {}", e);
+    }
 }
@@ -0,0 +1,127 @@
+//! The words that count as digits in part 2.
+//!
+//! A vocabulary file has one `word = digit` pair per line. Blank lines and
+//! lines starting with `#` are ignored, and several words may share a digit:
+//!
+//! ```text
+//! # French
+//! un = 1
+//! deux = 2
+//! ```
+
+use std::collections::HashMap;
+
+use aoc_common::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigitVocabulary {
+    words: Vec<(String, i64)>,
+}
+
+const ENGLISH : [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+impl Default for DigitVocabulary {
+    fn default() -> Self {
+        DigitVocabulary::english()
+    }
+}
+
+impl DigitVocabulary {
+    /// The words from the puzzle itself.
+    pub fn english() -> Self {
+        DigitVocabulary {
+            words: ENGLISH.iter()
+                .zip(1..)
+                .map(|(w, n)| (w.to_string(), n))
+                .collect()
+        }
+    }
+
+    /// No words at all, so only the digits count. That's part 1.
+    pub fn empty() -> Self {
+        DigitVocabulary { words: vec![] }
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let text = aoc_common::load_input(path)?;
+        DigitVocabulary::parse(&text)
+            .map_err(|e| Error::Input(format!("{}: {}", path, e)))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut seen : HashMap<&str, i64> = HashMap::new();
+        let mut words = vec![];
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let err = |msg: &str| format!("line {}: {}", n + 1, msg);
+
+            let (word, digit) = line.split_once('=')
+                .ok_or_else(|| err("expected word = digit"))?;
+            let (word, digit) = (word.trim(), digit.trim());
+
+            if word.is_empty() {
+                return Err(err("missing word"));
+            }
+            if word.chars().any(|c| c.is_ascii_digit()) {
+                return Err(err("words can't contain digits"));
+            }
+
+            let value = match digit.parse::<i64>() {
+                Ok(v) if (0..=9).contains(&v) => v,
+                _ => return Err(err("digit must be 0 to 9")),
+            };
+
+            match seen.insert(word, value) {
+                Some(old) if old != value =>
+                    return Err(err(&format!("{} is already {}", word, old))),
+                Some(_) => (),
+                None => words.push((word.to_string(), value)),
+            }
+        }
+
+        Ok(DigitVocabulary { words })
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.words.iter().map(|(w, n)| (w.as_str(), *n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_file() {
+        let v = DigitVocabulary::parse("# French\n\nun = 1\ndeux=2\n  trois = 3  \n")
+            .unwrap();
+
+        assert_eq!(v.words().collect::<Vec<_>>(),
+            [("un", 1), ("deux", 2), ("trois", 3)]);
+    }
+
+    #[test]
+    fn reject_bad_lines() {
+        for (text, why) in [
+            ("un 1", "no ="),
+            (" = 1", "no word"),
+            ("un = one", "not a digit"),
+            ("dix = 10", "not a single digit"),
+            ("un2 = 1", "word with a digit in it"),
+            ("un = 1\nun = 2", "word with two values"),
+        ] {
+            assert!(DigitVocabulary::parse(text).is_err(), "Rejected: {}", why);
+        }
+    }
+
+    #[test]
+    fn error_has_line_number() {
+        let e = DigitVocabulary::parse("un = 1\n\ndeux").unwrap_err();
+        assert!(e.starts_with("line 3:"), "{}", e);
+    }
+}
@@ -0,0 +1,175 @@
+//! Check the Rust day 1 solver against the two scripts it replaced, on random
+//! lines. The scripts themselves are gone, so their rules are reimplemented
+//! here as faithfully as possible, quirks included:
+//!
+//! - `soln.py` (part 1) took the first and last character that was a digit.
+//!   A line without one scored `10 * -1 + -1`, because -1 was its "not found".
+//! - `part2.pl` (part 2) replaced number words with digits left to right,
+//!   then took the first and last digit. That spoils overlapping words, and
+//!   a line without digits scored 0.
+//!
+//! The harness reports the first line where any of them disagree.
+
+use std::fmt;
+
+use aoc_common::Rng;
+use aoc_2023_12_01::{find_calibration_values, DigitVocabulary, Matcher, Mode};
+
+const NUMS : [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const OVERLAPS : [&str; 8] = ["eightwo", "oneight", "twone", "threeight", "fiveight",
+    "sevenine", "nineight", "eighthree"];
+// None of these letters appear in a number word, so they can't make one
+const FILLER : &[u8] = b"abcdjklmpqyz";
+
+/// Which kinds of awkward line the generator may produce.
+#[derive(Clone, Copy)]
+struct Generator {
+    overlaps: bool,
+    digit_free: bool,
+}
+
+impl Generator {
+    fn lines(self, seed: u64, count: usize) -> Vec<String> {
+        let mut rng = Rng::new(seed);
+        (0..count).map(|_| self.line(&mut rng)).collect()
+    }
+
+    fn line(self, rng: &mut Rng) -> String {
+        let digit_free = self.digit_free && rng.below(10) == 0;
+        let mut line = String::new();
+
+        for _ in 0..(1 + rng.below(6)) {
+            match rng.below(4) {
+                0 if !digit_free => line.push(char::from(b'1' + rng.below(9) as u8)),
+                1 => line.push_str(NUMS[rng.below(9)]),
+                2 if self.overlaps => line.push_str(OVERLAPS[rng.below(8)]),
+                _ => (),
+            }
+            // Keep whole tokens apart, so only the overlaps overlap
+            line.push(char::from(FILLER[rng.below(FILLER.len())]));
+        }
+
+        if !digit_free && !line.bytes().any(|b| b.is_ascii_digit()) {
+            line.push(char::from(b'1' + rng.below(9) as u8));
+        }
+
+        line
+    }
+}
+
+fn python_rule(line: &str) -> i64 {
+    let digits : Vec<i64> = line.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(i64::from)
+        .collect();
+
+    match (digits.first(), digits.last()) {
+        (Some(a), Some(b)) => 10 * a + b,
+        // 10 * -1 + -1, from the -1 it started both digits at
+        _ => -11,
+    }
+}
+
+fn perl_rule(line: &str) -> i64 {
+    let mut rewritten = String::new();
+    let mut rest = line;
+
+    // s/($restr)/$nums{$1}/g: take the leftmost word, then carry on after it
+    while let Some(c) = rest.chars().next() {
+        match NUMS.iter().position(|w| rest.starts_with(w)) {
+            Some(n) => {
+                rewritten.push_str(&(n + 1).to_string());
+                rest = &rest[NUMS[n].len()..];
+            }
+            None => {
+                rewritten.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    // Perl adds an empty string instead of the -11
+    match python_rule(&rewritten) {
+        -11 => 0,
+        n => n,
+    }
+}
+
+#[derive(Debug)]
+struct Divergence {
+    line_number: usize,
+    line: String,
+    rust: [Option<i64>; 2],
+    python: i64,
+    perl: i64,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} {:?}: rust part 1 {:?}, soln.py {}; rust part 2 {:?}, part2.pl {}",
+            self.line_number, self.line, self.rust[0], self.python, self.rust[1], self.perl)
+    }
+}
+
+fn first_divergence(lines: &[String]) -> Option<Divergence> {
+    let vocab = DigitVocabulary::english();
+    let part1 = Matcher::new(Mode::Digits, &vocab);
+    let part2 = Matcher::new(Mode::DigitsAndWords, &vocab);
+
+    lines.iter().enumerate().find_map(|(i, line)| {
+        let d = Divergence {
+            line_number: i + 1,
+            line: line.clone(),
+            rust: [
+                find_calibration_values(line, &part1),
+                find_calibration_values(line, &part2),
+            ],
+            python: python_rule(line),
+            perl: perl_rule(line),
+        };
+
+        let agree = d.rust[0] == Some(d.python) && d.rust[1] == Some(d.perl);
+        (!agree).then_some(d)
+    })
+}
+
+#[test]
+fn agree_on_ordinary_lines() {
+    let gen = Generator { overlaps: false, digit_free: false };
+
+    for seed in 1..=20 {
+        if let Some(d) = first_divergence(&gen.lines(seed, 1000)) {
+            panic!("Seed {}: {}", seed, d);
+        }
+    }
+}
+
+#[test]
+fn overlaps_diverge_from_perl_only() {
+    let gen = Generator { overlaps: true, digit_free: false };
+    let lines = gen.lines(2023, 1000);
+    let d = first_divergence(&lines).expect("Some overlap spoils part2.pl");
+
+    assert_eq!(d.rust[0], Some(d.python), "Part 1 still agrees: {}", d);
+    assert!(OVERLAPS.iter().any(|w| d.line.contains(w)),
+        "It's because of an overlap: {}", d);
+}
+
+#[test]
+fn digit_free_lines_diverge() {
+    let gen = Generator { overlaps: false, digit_free: true };
+    let lines = gen.lines(2023, 1000);
+    let d = first_divergence(&lines).expect("Some line has no digits");
+
+    assert_eq!(d.rust[0], None, "Rust has no value for it: {}", d);
+    assert_eq!(d.python, -11, "soln.py scores it -11: {}", d);
+}
+
+#[test]
+fn reference_rules() {
+    assert_eq!(python_rule("a1b2c3"), 13);
+    assert_eq!(python_rule("nothing"), -11);
+    assert_eq!(perl_rule("eightwothree"), 83);
+    assert_eq!(perl_rule("oneight"), 11, "The overlap bug");
+    assert_eq!(perl_rule("nothing"), 0);
+}
@@ -0,0 +1,150 @@
+//! How fast day 2 reads games, against the regexes it used to compile for
+//! every hand: one for each of red, green and blue, and another for the game
+//! ID on every line. Run with `cargo bench -p aoc-2023-12-02`; pass a number
+//! of games to change how many are generated.
+//!
+//! The old way is so slow that it only gets a sample of the games. It only
+//! knew about three colours, so those are the ones generated.
+
+use std::env;
+use std::time::Instant;
+
+use aoc_2023_12_02::generate::Generator;
+use aoc_2023_12_02::{max_of_each_colour, power, str_to_game, str_to_game_into, Day02};
+use regex::Regex;
+
+const LEGACY_SAMPLE : usize = 2_000;
+
+fn main() {
+    let games = env::args()
+        .skip(1)
+        .find_map(|a| a.parse::<usize>().ok())
+        .unwrap_or(1_000_000);
+    let input = generate(games);
+
+    measure("str_to_game", &input, |text| text.lines()
+        .map(|l| str_to_game(l).unwrap())
+        .map(|g| power(&max_of_each_colour(&g.hands)).unwrap())
+        .sum());
+
+    measure("reused Game", &input, |text| {
+        let mut g = Default::default();
+        text.lines()
+            .map(|l| {
+                str_to_game_into(l, &mut g).unwrap();
+                power(&max_of_each_colour(&g.hands)).unwrap()
+            })
+            .sum()
+    });
+
+    measure("Day02::tally", &input, |text| Day02::default().tally(text.into()).unwrap().powersum);
+
+    let sample_end = input.match_indices('\n').nth(LEGACY_SAMPLE - 1).map_or(input.len(), |(i, _)| i + 1);
+    let sample = &input[..sample_end];
+    let old = measure("per-hand regex", sample, |text| text.lines()
+        .map(legacy_str_to_game)
+        .map(|g| legacy_power(&legacy_max_of_each_colour(&g.hands)))
+        .sum());
+
+    assert_eq!(Day02::default().tally(sample.into()).unwrap().powersum, old,
+        "Both implementations agree");
+}
+
+fn measure(name: &str, input: &str, f: impl Fn(&str) -> i64) -> i64 {
+    let games = input.lines().count();
+    let start = Instant::now();
+    let total = f(input);
+    let secs = start.elapsed().as_secs_f64();
+
+    println!("{:>16}: {:>10.2} MB/s {:>12.0} games/s ({:.3}s)",
+        name, input.len() as f64 / (1024.0 * 1024.0) / secs, games as f64 / secs, secs);
+
+    total
+}
+
+fn generate(games: usize) -> String {
+    let generator = Generator {
+        games,
+        colours: ["red", "green", "blue"].map(String::from).to_vec(),
+        seed: 0x2023_1202,
+        ..Default::default()
+    };
+
+    let mut out = String::new();
+    for game in generator.games() {
+        out.push_str(&game.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+// The old parser, as it was apart from names and lints
+
+struct LegacyHand {
+    rgb: [Option<i64>; 3],
+}
+
+struct LegacyGame {
+    hands: Vec<LegacyHand>,
+    // Still read, but only part 1 used it
+    #[allow(dead_code)]
+    id: i64,
+}
+
+fn legacy_str_to_game(string: &str) -> LegacyGame {
+    // it doesn't really matter that the first split has the Game N: part
+    let hands = string.split(';').map(legacy_str_to_hand);
+    let id = legacy_get_game_id(string);
+
+    LegacyGame {
+        hands: hands.collect::<Vec<LegacyHand>>(),
+        id,
+    }
+}
+
+fn legacy_str_to_hand(string: &str) -> LegacyHand {
+    let re_red = Regex::new(r"(\d+) red").unwrap();
+    let re_green = Regex::new(r"(\d+) green").unwrap();
+    let re_blue = Regex::new(r"(\d+) blue").unwrap();
+
+    LegacyHand {
+        rgb: [
+            legacy_one_int_from_str(string, re_red),
+            legacy_one_int_from_str(string, re_green),
+            legacy_one_int_from_str(string, re_blue),
+        ]
+    }
+}
+
+fn legacy_one_int_from_str(string: &str, re: Regex) -> Option<i64> {
+    let res = re.captures(string)?;
+    let (_, [num]) = res.extract();
+    Some(num.parse::<i64>().unwrap())
+}
+
+fn legacy_get_game_id(line: &str) -> i64 {
+    let re = Regex::new(r"Game (\d+)").unwrap();
+    legacy_one_int_from_str(line, re).unwrap()
+}
+
+fn legacy_max_of_each_colour(hands: &[LegacyHand]) -> LegacyHand {
+    let mut hand = LegacyHand {
+        rgb: [None, None, None]
+    };
+
+    for h in hands {
+        for n in 0..=2 {
+            hand.rgb[n] = match (hand.rgb[n], h.rgb[n]) {
+                (x,        None)     => x,
+                (None,     Some(x))  => Some(x),
+                (Some(x1), Some(x2)) => Some(std::cmp::max(x1, x2))
+            };
+        }
+    }
+
+    hand
+}
+
+fn legacy_power(hand: &LegacyHand) -> i64 {
+    hand.rgb.iter().map(|x| x.unwrap_or(1)).product()
+}
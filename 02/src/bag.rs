@@ -0,0 +1,126 @@
+//! The bags that games are checked against in part 1.
+//!
+//! A bag can be given on the command line as `red=12,green=13,blue=14`, or
+//! several can be given in a TOML file with a table for each:
+//!
+//! ```toml
+//! [puzzle]
+//! red = 12
+//! green = 13
+//! blue = 14
+//! ```
+
+use aoc_common::Error;
+
+use crate::Hand;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bag {
+    pub name: String,
+    pub cubes: Hand,
+}
+
+impl Bag {
+    /// The bag from the puzzle itself.
+    pub fn puzzle() -> Self {
+        Bag::parse("red=12,green=13,blue=14").unwrap()
+    }
+
+    /// A bag written as `colour=count,colour=count`. It's named after itself.
+    pub fn parse(spec: &str) -> Result<Self, Error> {
+        let err = |msg: String| Error::Usage(format!("bag {:?}: {}", spec, msg));
+        let mut cubes : Hand = Default::default();
+
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (colour, count) = part.split_once('=')
+                .ok_or_else(|| err(format!("expected colour=count, not {:?}", part)))?;
+            let (colour, count) = (colour.trim(), count.trim());
+
+            let count = count.parse::<i64>().ok()
+                .filter(|n| *n >= 0)
+                .ok_or_else(|| err(format!("{:?} is not a number of cubes", count)))?;
+            add_colour(&mut cubes, colour, count).map_err(err)?;
+        }
+
+        Ok(Bag {
+            name: spec.to_string(),
+            cubes,
+        })
+    }
+
+    /// Every bag in a TOML file, in the order they're written.
+    pub fn load(path: &str) -> Result<Vec<Self>, Error> {
+        let text = aoc_common::load_input(path)?;
+        Bag::parse_toml(&text)
+            .map_err(|msg| Error::Input(format!("{}: {}", path, msg)))
+    }
+
+    pub fn parse_toml(text: &str) -> Result<Vec<Self>, String> {
+        let table : toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let mut bags = vec![];
+
+        for (name, colours) in table {
+            let colours = colours.as_table()
+                .ok_or_else(|| format!("{} should be a table of colours", name))?;
+            let mut cubes : Hand = Default::default();
+
+            for (colour, count) in colours {
+                let count = count.as_integer()
+                    .filter(|n| *n >= 0)
+                    .ok_or_else(|| format!("{}.{} is not a number of cubes", name, colour))?;
+                add_colour(&mut cubes, colour, count)
+                    .map_err(|msg| format!("{}: {}", name, msg))?;
+            }
+
+            bags.push(Bag { name, cubes });
+        }
+
+        Ok(bags)
+    }
+}
+
+fn add_colour(hand: &mut Hand, colour: &str, count: i64) -> Result<(), String> {
+    if colour.is_empty() {
+        return Err(String::from("missing colour"));
+    }
+    if hand.cubes.insert(colour.to_string(), count).is_some() {
+        return Err(format!("{} is there twice", colour));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_spec() {
+        let bag = Bag::parse("red=12, green = 13,blue=14").unwrap();
+
+        assert_eq!(bag.name, "red=12, green = 13,blue=14");
+        assert_eq!(bag.cubes.get("green"), 13);
+        assert_eq!(bag.cubes.cubes.len(), 3);
+        assert_eq!(bag.cubes, Bag::puzzle().cubes);
+    }
+
+    #[test]
+    fn reject_bad_specs() {
+        for spec in ["red", "red=", "red=-1", "=3", "red=1,red=2", "red=lots"] {
+            assert!(Bag::parse(spec).is_err(), "{} is rejected", spec);
+        }
+    }
+
+    #[test]
+    fn parse_toml_file() {
+        let bags = Bag::parse_toml("[small]\nred = 1\n\n[big]\nred = 99\nteal = 5\n")
+            .unwrap();
+
+        assert_eq!(bags.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+            ["small", "big"], "Bags are in file order");
+        assert_eq!(bags[1].cubes.get("teal"), 5);
+
+        assert!(Bag::parse_toml("red = 1").is_err(), "Bags must be tables");
+        assert!(Bag::parse_toml("[b]\nred = \"x\"").is_err(), "Counts are numbers");
+    }
+}
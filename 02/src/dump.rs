@@ -0,0 +1,139 @@
+//! Each game as it was understood, for `--dump`, along with the smallest bag
+//! it fits in and that bag's power.
+
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use crate::{max_of_each_colour, Game, Hand, Total};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// One JSON object per game.
+    Json,
+    /// One row per colour in each hand, so it doesn't matter which colours
+    /// there are.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = aoc_common::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(aoc_common::Error::Usage(
+                format!("--dump must be json or csv, not {:?}", s))),
+        }
+    }
+}
+
+impl Format {
+    /// What goes before the first game, if anything.
+    pub fn header(&self) -> Option<&'static str> {
+        match self {
+            Format::Json => None,
+            Format::Csv => Some("game,hand,colour,count,max,power"),
+        }
+    }
+}
+
+/// The power is a `T`, like the answers, so it can be a `BigInt` with
+/// `--bigint`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary<'a, T: Total = i64> {
+    pub id: i64,
+    pub hands: &'a [Hand],
+    pub max: Hand,
+    #[serde(serialize_with = "as_number")]
+    pub power: T,
+}
+
+impl<'a, T: Total> Summary<'a, T> {
+    /// Only fails if the power is too big for a `T`.
+    pub fn new(game: &'a Game) -> Result<Self, aoc_common::Error> {
+        let max = max_of_each_colour(&game.hands);
+        let mut power = T::default();
+        if !power.add_product(max.cubes.values().copied()) {
+            return Err(aoc_common::Error::Input(format!(
+                "game {}: the power is too big for 64 bits (--bigint has no limit)", game.id)));
+        }
+
+        Ok(Summary {
+            id: game.id,
+            hands: &game.hands,
+            max,
+            power,
+        })
+    }
+
+    /// No line ending after the last line.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Json => serde_json::to_string(self).unwrap(),
+            Format::Csv => {
+                let mut rows = vec![];
+                // Hands count from 1, like the report
+                for (i, hand) in self.hands.iter().enumerate() {
+                    for (colour, n) in &hand.cubes {
+                        rows.push(format!("{},{},{},{},{},{}",
+                            self.id, i + 1, colour, n, self.max.get(colour), self.power));
+                    }
+                }
+                rows.join("\n")
+            }
+        }
+    }
+}
+
+/// A JSON number of any size, where a `BigInt` would otherwise be a list of
+/// its digits.
+fn as_number<T: Total, S: Serializer>(n: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    RawValue::from_string(n.to_string()).unwrap().serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_game;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn json_and_csv() {
+        let g = str_to_game("Game 3: 3 blue, 4 red; 1 red, 6 blue; 2 teal").unwrap();
+        let summary = Summary::<i64>::new(&g).unwrap();
+
+        assert_eq!(summary.render(Format::Json), concat!(
+            r#"{"id":3,"hands":[{"blue":3,"red":4},{"blue":6,"red":1},{"teal":2}],"#,
+            r#""max":{"blue":6,"red":4,"teal":2},"power":48}"#));
+        assert_eq!(summary.render(Format::Csv), [
+            "3,1,blue,3,6,48",
+            "3,1,red,4,4,48",
+            "3,2,blue,6,6,48",
+            "3,2,red,1,4,48",
+            "3,3,teal,2,2,48",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn power_too_big() {
+        let g = str_to_game("Game 2: 4294967296 red, 4294967296 blue").unwrap();
+        assert!(Summary::<i64>::new(&g).is_err());
+
+        let summary = Summary::<BigInt>::new(&g).unwrap();
+        assert!(summary.render(Format::Json).ends_with(r#""power":18446744073709551616}"#),
+            "Big, but still a number");
+        assert!(summary.render(Format::Csv).ends_with(",18446744073709551616"));
+    }
+
+    #[test]
+    fn games_round_trip_through_json() {
+        let g = str_to_game("Game 9: 1 mauve; 2 ochre, 5 red").unwrap();
+        let json = serde_json::to_string(&g).unwrap();
+
+        assert_eq!(json, r#"{"hands":[{"mauve":1},{"ochre":2,"red":5}],"id":9}"#);
+        assert_eq!(serde_json::from_str::<Game>(&json).unwrap(), g);
+    }
+}
@@ -0,0 +1,304 @@
+//! Made-up games, as many as you like, for benchmarks and fuzzing. They're
+//! written the same way the puzzle input is, so they read back in exactly.
+
+use std::str::FromStr;
+
+use aoc_common::{Error, Rng};
+
+use crate::{is_game_possible, Game, Hand};
+
+/// How many cubes of a colour a hand shows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Counts {
+    /// Anything from `low` to `high`, evenly.
+    Uniform { low: i64, high: i64 },
+    /// Mostly small, now and then big, and `mean` on average.
+    Geometric { mean: f64 },
+}
+
+impl Counts {
+    fn sample(&self, rng: &mut Rng) -> i64 {
+        match *self {
+            Counts::Uniform { low, high } => low + rng.below((high - low + 1) as usize) as i64,
+            Counts::Geometric { mean } => {
+                let mut n = 1;
+                while rng.unit() >= 1.0 / mean {
+                    n += 1;
+                }
+                n
+            }
+        }
+    }
+}
+
+/// The biggest geometric mean. Each cube takes a step to sample, so a bigger
+/// one would take forever.
+pub const MAX_MEAN : f64 = 1e6;
+
+impl FromStr for Counts {
+    type Err = Error;
+
+    /// `uniform:LOW-HIGH` or `geometric:MEAN`. Counts have to fit in the
+    /// `i64`s the input is read into, with room for a game to go over.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || Error::Usage(format!(
+            "--counts must be uniform:LOW-HIGH up to {} or geometric:MEAN up to {}, not {:?}",
+            i64::MAX - 1, MAX_MEAN, s));
+
+        match s.split_once(':') {
+            Some(("uniform", range)) => {
+                let (low, high) = parse_range::<i64>(range)
+                    .filter(|(_, high)| *high < i64::MAX)
+                    .ok_or_else(err)?;
+                Ok(Counts::Uniform { low, high })
+            }
+            Some(("geometric", mean)) => mean.parse::<f64>().ok()
+                .filter(|m| (1.0..=MAX_MEAN).contains(m))
+                .map(|mean| Counts::Geometric { mean })
+                .ok_or_else(err),
+            _ => Err(err()),
+        }
+    }
+}
+
+/// `N` or `LOW-HIGH`, which must be at least 1 and the right way round.
+pub fn parse_range<T: FromStr + Ord + Default>(s: &str) -> Option<(T, T)> {
+    let (low, high) = s.split_once('-').unwrap_or((s, s));
+    let (low, high) = (low.trim().parse::<T>().ok()?, high.trim().parse::<T>().ok()?);
+
+    if low <= T::default() || low > high {
+        return None;
+    }
+    Some((low, high))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    pub games: usize,
+    /// The fewest and most hands in a game.
+    pub hands: (usize, usize),
+    pub colours: Vec<String>,
+    pub counts: Counts,
+    /// A bag, and how much of the time games should be possible with it.
+    /// Without one, games are whatever comes out.
+    pub possible: Option<(Hand, f64)>,
+    pub seed: u64,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator {
+            games: 100,
+            hands: (1, 6),
+            colours: ["red", "green", "blue"].map(String::from).to_vec(),
+            counts: Counts::Uniform { low: 1, high: 20 },
+            possible: None,
+            seed: 1,
+        }
+    }
+}
+
+impl Generator {
+    /// Make sure every game can be made, and will read back in.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.colours.is_empty() {
+            return Err(Error::Usage(String::from("there must be at least one colour")));
+        }
+        for colour in &self.colours {
+            if colour.is_empty() || !colour.chars().all(char::is_alphabetic) {
+                return Err(Error::Usage(format!("{:?} is not a colour", colour)));
+            }
+        }
+        if let Some((bag, fraction)) = &self.possible {
+            if !(0.0..=1.0).contains(fraction) {
+                return Err(Error::Usage(format!("{} is not a fraction of games", fraction)));
+            }
+            if *fraction > 0.0 && self.colours.iter().all(|c| bag.get(c) == 0) {
+                return Err(Error::Usage(String::from(
+                    "no game can be possible with none of its colours in the bag")));
+            }
+            if *fraction < 1.0 && self.colours.iter().all(|c| bag.get(c) == i64::MAX) {
+                return Err(Error::Usage(String::from(
+                    "no game can be impossible with as many of every colour as 64 bits hold")));
+            }
+        }
+        Ok(())
+    }
+
+    /// The games, one at a time, with IDs from 1.
+    pub fn games(&self) -> impl Iterator<Item = Game> + '_ {
+        let mut rng = Rng::new(self.seed);
+        (0..self.games).map(move |i| self.game(&mut rng, i))
+    }
+
+    fn game(&self, rng: &mut Rng, i: usize) -> Game {
+        let (fewest, most) = self.hands;
+        let hands = fewest + rng.below(most - fewest + 1);
+
+        let Some((bag, fraction)) = &self.possible
+        else {
+            return Game {
+                hands: (0..hands).map(|_| self.hand(rng, &self.colours, None)).collect(),
+                id: i as i64 + 1,
+            };
+        };
+
+        // Spread the possible games out evenly, so any stretch of them has
+        // about the right share
+        let possible = ((i + 1) as f64 * fraction).floor() > (i as f64 * fraction).floor();
+
+        if possible {
+            let colours : Vec<String> = self.colours.iter()
+                .filter(|c| bag.get(c) > 0)
+                .cloned()
+                .collect();
+            return Game {
+                hands: (0..hands).map(|_| self.hand(rng, &colours, Some(bag))).collect(),
+                id: i as i64 + 1,
+            };
+        }
+
+        let mut game = Game {
+            hands: (0..hands).map(|_| self.hand(rng, &self.colours, None)).collect(),
+            id: i as i64 + 1,
+        };
+
+        // Didn't come out impossible by itself, so one colour goes over. It
+        // can't be one the bag has as many of as there can be, and any other
+        // goes as far over as it can.
+        if is_game_possible(&game, bag) {
+            let colours : Vec<&String> = self.colours.iter()
+                .filter(|c| bag.get(c) < i64::MAX)
+                .collect();
+            let colour = colours[rng.below(colours.len())];
+            let over = bag.get(colour).checked_add(self.counts.sample(rng)).unwrap_or(i64::MAX);
+            game.hands[rng.below(hands)].cubes.insert(colour.clone(), over);
+        }
+
+        game
+    }
+
+    /// Some of `colours` in a random order, with none over what's in `bag`.
+    fn hand(&self, rng: &mut Rng, colours: &[String], bag: Option<&Hand>) -> Hand {
+        let mut colours = colours.to_vec();
+        rng.shuffle(&mut colours);
+        colours.truncate(1 + rng.below(colours.len()));
+
+        Hand {
+            cubes: colours.into_iter()
+                .map(|c| {
+                    let n = self.counts.sample(rng);
+                    let n = bag.map_or(n, |b| n.min(b.get(&c)));
+                    (c, n)
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{str_to_game, Bag};
+
+    #[test]
+    fn round_trip() {
+        let generator = Generator {
+            games: 500,
+            colours: ["red", "teal", "grün"].map(String::from).to_vec(),
+            counts: Counts::Geometric { mean: 4.0 },
+            ..Default::default()
+        };
+
+        for game in generator.games() {
+            let line = game.to_string();
+            assert!(line.starts_with(&format!("Game {}: ", game.id)));
+            assert_eq!(str_to_game(&line).unwrap(), game, "{} reads back in", line);
+        }
+    }
+
+    #[test]
+    fn shape() {
+        let generator = Generator {
+            games: 200,
+            hands: (2, 3),
+            counts: Counts::Uniform { low: 5, high: 7 },
+            ..Default::default()
+        };
+        let games : Vec<Game> = generator.games().collect();
+
+        assert_eq!(games.len(), 200);
+        assert!(games.iter().all(|g| (2..=3).contains(&g.hands.len())));
+        assert!(games.iter().flat_map(|g| &g.hands).flat_map(|h| h.cubes.values())
+            .all(|n| (5..=7).contains(n)));
+        assert_eq!(games, generator.games().collect::<Vec<_>>(), "Same seed, same games");
+    }
+
+    #[test]
+    fn fraction_possible() {
+        let bag = Bag::puzzle().cubes;
+
+        for fraction in [0.0, 0.25, 0.5, 0.9, 1.0] {
+            let generator = Generator {
+                games: 1000,
+                colours: ["red", "green", "blue", "teal"].map(String::from).to_vec(),
+                possible: Some((bag.clone(), fraction)),
+                ..Default::default()
+            };
+            generator.check().unwrap();
+
+            let possible = generator.games().filter(|g| is_game_possible(g, &bag)).count();
+            assert_eq!(possible, (1000.0 * fraction) as usize, "{} of games possible", fraction);
+        }
+    }
+
+    #[test]
+    fn bag_at_the_limit() {
+        let bag = Bag::parse("red=9223372036854775807,green=9223372036854775806").unwrap().cubes;
+        let generator = Generator {
+            games: 50,
+            colours: ["red", "green"].map(String::from).to_vec(),
+            possible: Some((bag.clone(), 0.0)),
+            ..Default::default()
+        };
+        generator.check().unwrap();
+
+        assert!(generator.games().all(|g| !is_game_possible(&g, &bag)),
+            "Green can still go over, just");
+
+        let full = Generator {
+            colours: vec![String::from("red")],
+            ..generator
+        };
+        assert!(full.check().is_err(), "Nothing is more red than that");
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!("uniform:1-20".parse::<Counts>().unwrap(), Counts::Uniform { low: 1, high: 20 });
+        assert_eq!("geometric:2.5".parse::<Counts>().unwrap(), Counts::Geometric { mean: 2.5 });
+        for bad in ["uniform:5-1", "uniform:0-3", "geometric:0.5", "poisson:3", "uniform",
+            "uniform:1-18446744073709551615", "uniform:1-9223372036854775807", "geometric:1e18"]
+        {
+            assert!(bad.parse::<Counts>().is_err(), "{} is rejected", bad);
+        }
+
+        assert_eq!(parse_range::<usize>("4"), Some((4, 4)));
+        assert_eq!(parse_range::<usize>("1-6"), Some((1, 6)));
+
+        let biggest = Generator {
+            games: 20,
+            counts: "uniform:9223372036854775000-9223372036854775806".parse().unwrap(),
+            ..Default::default()
+        };
+        for game in biggest.games() {
+            assert_eq!(str_to_game(&game.to_string()).unwrap(), game, "Still reads back in");
+        }
+
+        let bad_colour = Generator {
+            colours: vec![String::from("light blue")],
+            ..Default::default()
+        };
+        assert!(bad_colour.check().is_err(), "Wouldn't read back in");
+    }
+}
@@ -0,0 +1,368 @@
+//! How likely the recorded games are, if the elf had a particular bag.
+//!
+//! Each hand is a fresh draw from the full bag, since the cubes go back in
+//! between hands, so a game's likelihood is the product of its hands'. Within
+//! a hand the cubes can be drawn either putting each back before the next
+//! (a multinomial) or keeping them out (a multivariate hypergeometric). The
+//! order they came out in isn't recorded, so only the counts matter.
+//!
+//! Everything is a natural log, because a hundred games' worth of
+//! probabilities would underflow. Impossible is negative infinity.
+
+use std::str::FromStr;
+
+use aoc_common::{Error, Rng};
+
+use crate::{Bag, Game, Hand};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Draws {
+    /// Each cube goes back in the bag before the next is drawn.
+    WithReplacement,
+    /// The elf grabs a handful.
+    #[default]
+    WithoutReplacement,
+}
+
+impl FromStr for Draws {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "with" => Ok(Draws::WithReplacement),
+            "without" => Ok(Draws::WithoutReplacement),
+            _ => Err(Error::Usage(format!("--likelihood must be with or without, not {:?}", s))),
+        }
+    }
+}
+
+/// How many cubes there are altogether, unless it's more than an `i64` holds.
+fn cube_count(hand: &Hand) -> Result<i64, Error> {
+    hand.cubes.values()
+        .try_fold(0i64, |sum, n| sum.checked_add(*n))
+        .ok_or_else(|| Error::Input(format!("{} is too many cubes to count in 64 bits", hand)))
+}
+
+/// The log of the chance of drawing exactly `hand` from `bag`. Only fails if
+/// there are too many cubes in either to count.
+pub fn hand_log_likelihood(hand: &Hand, bag: &Hand, draws: Draws) -> Result<f64, Error> {
+    let drawn = cube_count(hand)?;
+    let total = cube_count(bag)?;
+
+    // Drawing nothing always works, even from an empty bag
+    if drawn == 0 {
+        return Ok(0.0);
+    }
+    if hand.cubes.iter().any(|(colour, n)| *n > 0 && bag.get(colour) == 0) {
+        return Ok(f64::NEG_INFINITY);
+    }
+
+    Ok(match draws {
+        Draws::WithReplacement => {
+            // K! / (k1! k2! ...) * p1^k1 * p2^k2 ..., where a colour that
+            // wasn't drawn is a factor of 1, even if its p is 0
+            hand.cubes.iter()
+                .filter(|(_, n)| **n > 0)
+                .fold(ln_factorial(drawn), |acc, (colour, n)| {
+                    let p = bag.get(colour) as f64 / total as f64;
+                    acc - ln_factorial(*n) + *n as f64 * p.ln()
+                })
+        }
+        Draws::WithoutReplacement => {
+            // C(n1, k1) C(n2, k2) ... / C(N, K)
+            if hand.cubes.iter().any(|(colour, n)| *n > bag.get(colour)) {
+                return Ok(f64::NEG_INFINITY);
+            }
+            hand.cubes.iter()
+                .map(|(colour, n)| ln_choose(bag.get(colour), *n))
+                .sum::<f64>() - ln_choose(total, drawn)
+        }
+    })
+}
+
+/// The log of the chance of every hand in the game, in turn.
+pub fn log_likelihood(game: &Game, bag: &Hand, draws: Draws) -> Result<f64, Error> {
+    game.hands.iter().map(|h| hand_log_likelihood(h, bag, draws)).sum()
+}
+
+/// The most cubes a simulated hand can have. Each one is drawn in turn, on
+/// every trial, so much bigger hands would never finish.
+pub const MAX_SIMULATED_HAND : i64 = 1_000_000;
+
+/// Estimates the same likelihoods by drawing from the bag over and over.
+pub struct Simulation {
+    rng: Rng,
+    trials: usize,
+}
+
+impl Simulation {
+    /// No trials would be no estimate at all, so that's an error.
+    pub fn new(seed: u64, trials: usize) -> Result<Self, Error> {
+        if trials == 0 {
+            return Err(Error::Usage(String::from("--simulate needs at least one trial")));
+        }
+
+        Ok(Simulation {
+            rng: Rng::new(seed),
+            trials,
+        })
+    }
+
+    /// The log of how often `hand` came up in `trials` draws. A hand that's
+    /// rare enough might not come up at all, and then it looks impossible.
+    ///
+    /// Only the number of each colour left in the bag is kept, not the cubes
+    /// themselves, so a bag of billions is no harder than a bag of ten.
+    pub fn hand_log_likelihood(&mut self, hand: &Hand, bag: &Hand, draws: Draws)
+        -> Result<f64, Error>
+    {
+        let drawn = cube_count(hand)?;
+        let total = cube_count(bag)?;
+        if drawn > MAX_SIMULATED_HAND {
+            return Err(Error::Input(format!("{} is too many cubes to simulate drawing (at most {})",
+                hand, MAX_SIMULATED_HAND)));
+        }
+
+        let colours : Vec<&String> = bag.cubes.keys().collect();
+        let in_bag : Vec<i64> = bag.cubes.values().copied().collect();
+
+        let want : Vec<i64> = colours.iter().map(|c| hand.get(c)).collect();
+        if hand.cubes.iter().any(|(colour, n)| *n > 0 && bag.get(colour) == 0)
+            || (draws == Draws::WithoutReplacement && drawn > total)
+        {
+            return Ok(f64::NEG_INFINITY);
+        }
+
+        let mut hits = 0;
+        let mut left = in_bag.clone();
+        let mut got = vec![0; colours.len()];
+
+        for _ in 0..self.trials {
+            left.copy_from_slice(&in_bag);
+            got.fill(0);
+            let mut remaining = total;
+
+            for _ in 0..drawn {
+                // Pick a cube, then count along the colours to see which
+                // one it is
+                let mut cube = self.rng.below(remaining as usize) as i64;
+                let colour = left.iter()
+                    .position(|n| cube < *n || { cube -= n; false })
+                    .unwrap();
+
+                got[colour] += 1;
+                if draws == Draws::WithoutReplacement {
+                    left[colour] -= 1;
+                    remaining -= 1;
+                }
+            }
+
+            if got == want {
+                hits += 1;
+            }
+        }
+
+        Ok((hits as f64 / self.trials as f64).ln())
+    }
+
+    pub fn log_likelihood(&mut self, game: &Game, bag: &Hand, draws: Draws) -> Result<f64, Error> {
+        game.hands.iter().map(|h| self.hand_log_likelihood(h, bag, draws)).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranked<'a> {
+    pub bag: &'a Bag,
+    pub log_likelihood: f64,
+}
+
+/// Every bag with the likelihood of all the games together, most likely
+/// first. Bags that tie stay in the order they were given.
+pub fn rank<'a>(bags: &'a [Bag], games: &[Game], draws: Draws) -> Result<Vec<Ranked<'a>>, Error> {
+    let mut ranked = vec![];
+    for bag in bags {
+        let likelihood = games.iter()
+            .map(|g| log_likelihood(g, &bag.cubes, draws))
+            .sum::<Result<f64, _>>()?;
+        ranked.push(Ranked { bag, log_likelihood: likelihood });
+    }
+
+    ranked.sort_by(|a, b| b.log_likelihood.total_cmp(&a.log_likelihood));
+    Ok(ranked)
+}
+
+/// Up to here, logs of factorials are added up a step at a time. Past it,
+/// Stirling's series is as close as an `f64` can tell, and doesn't take a
+/// step per cube.
+const EXACT_FACTORIALS : i64 = 256;
+
+fn ln_factorial(n: i64) -> f64 {
+    if n <= EXACT_FACTORIALS {
+        return (2..=n).map(|i| (i as f64).ln()).sum();
+    }
+
+    let n = n as f64;
+    n * n.ln() - n + 0.5 * (2.0 * std::f64::consts::PI * n).ln()
+        + 1.0 / (12.0 * n) - 1.0 / (360.0 * n.powi(3))
+}
+
+/// `k` must be no more than `n`.
+fn ln_choose(n: i64, k: i64) -> f64 {
+    let k = k.min(n - k);
+
+    // Only k steps, since bags can be much bigger than hands, and taking the
+    // difference of huge factorials would lose the small ones' precision
+    if k <= EXACT_FACTORIALS {
+        return (0..k).map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln()).sum();
+    }
+    ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_game;
+
+    fn bag(spec: &str) -> Hand {
+        Bag::parse(spec).unwrap().cubes
+    }
+
+    fn hand(text: &str) -> Hand {
+        str_to_game(&format!("Game 1: {}", text)).unwrap().hands.remove(0)
+    }
+
+    fn close(a: f64, b: f64, within: f64) -> bool {
+        (a - b).abs() < within
+    }
+
+    #[test]
+    fn exact_likelihoods() {
+        let b = bag("red=2,blue=1");
+        let one_each = hand("1 red, 1 blue");
+
+        assert!(close(hand_log_likelihood(&one_each, &b, Draws::WithoutReplacement).unwrap().exp(),
+            2.0 / 3.0, 1e-12));
+        assert!(close(hand_log_likelihood(&one_each, &b, Draws::WithReplacement).unwrap().exp(),
+            4.0 / 9.0, 1e-12));
+
+        let two_blue = hand("2 blue");
+        assert_eq!(hand_log_likelihood(&two_blue, &b, Draws::WithoutReplacement).unwrap(),
+            f64::NEG_INFINITY, "Only one blue to take");
+        assert!(close(hand_log_likelihood(&two_blue, &b, Draws::WithReplacement).unwrap().exp(),
+            1.0 / 9.0, 1e-12), "Unless it goes back in");
+
+        assert_eq!(hand_log_likelihood(&hand("1 teal"), &b, Draws::WithReplacement).unwrap(),
+            f64::NEG_INFINITY, "No teal at all");
+    }
+
+    #[test]
+    fn nothing_drawn() {
+        let b = bag("blue=5");
+
+        for draws in [Draws::WithReplacement, Draws::WithoutReplacement] {
+            let no_red = hand_log_likelihood(&hand("0 red, 2 blue"), &b, draws).unwrap();
+            assert!(close(no_red, 0.0, 1e-12),
+                "{:?}: no red is no different from no mention of red", draws);
+            assert_eq!(hand_log_likelihood(&hand("0 red"), &bag(""), draws).unwrap(), 0.0,
+                "{:?}: nothing from nothing", draws);
+        }
+    }
+
+    #[test]
+    fn games_multiply() {
+        let g = str_to_game("Game 1: 1 red, 1 blue; 2 red").unwrap();
+        let b = bag("red=2,blue=1");
+
+        // 2/3 for the first hand, then 1/3 for both reds
+        assert!(close(log_likelihood(&g, &b, Draws::WithoutReplacement).unwrap().exp(),
+            2.0 / 9.0, 1e-12));
+    }
+
+    #[test]
+    fn simulation_agrees() {
+        let b = bag("red=5,green=3,blue=4");
+        let mut sim = Simulation::new(2023, 20_000).unwrap();
+
+        for h in ["2 red, 1 blue", "1 green", "3 red, 2 green, 1 blue", "4 blue"] {
+            let h = hand(h);
+            for draws in [Draws::WithReplacement, Draws::WithoutReplacement] {
+                let exact = hand_log_likelihood(&h, &b, draws).unwrap().exp();
+                let simulated = sim.hand_log_likelihood(&h, &b, draws).unwrap().exp();
+                assert!(close(exact, simulated, 0.015),
+                    "{:?} {:?}: {} against {}", h, draws, exact, simulated);
+            }
+        }
+
+        assert_eq!(sim.hand_log_likelihood(&hand("5 blue"), &b, Draws::WithoutReplacement)
+            .unwrap(),
+            f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn simulate_huge_bags() {
+        let b = bag("red=100000000000,blue=300000000000");
+        let mut sim = Simulation::new(7, 20_000).unwrap();
+
+        for draws in [Draws::WithReplacement, Draws::WithoutReplacement] {
+            let simulated = sim.hand_log_likelihood(&hand("1 red, 1 blue"), &b, draws)
+                .unwrap()
+                .exp();
+            assert!(close(simulated, 0.375, 0.015), "{:?}: {}", draws, simulated);
+        }
+    }
+
+    #[test]
+    fn too_many_cubes() {
+        let b = bag("red=9223372036854775807,blue=1");
+
+        assert!(hand_log_likelihood(&hand("1 red"), &b, Draws::WithReplacement).is_err(),
+            "The bag's total doesn't fit");
+        assert!(Simulation::new(1, 1).unwrap()
+            .hand_log_likelihood(&hand("1 red"), &b, Draws::WithReplacement).is_err());
+    }
+
+    #[test]
+    fn huge_hands() {
+        let exact : f64 = (2..=1000).map(|i| (i as f64).ln()).sum();
+        assert!(close(ln_factorial(1000), exact, 1e-9), "Stirling agrees");
+        assert!(close(ln_choose(1000, 400), ln_factorial(1000) - ln_factorial(400) - ln_factorial(600),
+            1e-9));
+
+        let b = bag("red=10000000000000,blue=10000000000000");
+        let red = hand("9000000000000 red");
+        // Half red, every time, or the hypergeometric as worked out by lgamma
+        let expected = [
+            (Draws::WithReplacement, 9e12 * 0.5f64.ln()),
+            (Draws::WithoutReplacement, -10511946540356.41),
+        ];
+        for (draws, expected) in expected {
+            let l = hand_log_likelihood(&red, &b, draws).unwrap();
+            assert!(((l - expected) / expected).abs() < 1e-9, "{:?}: {} against {}", draws, l, expected);
+        }
+        assert!(Simulation::new(1, 1).unwrap()
+            .hand_log_likelihood(&red, &b, Draws::WithReplacement).is_err(),
+            "Too big to draw a cube at a time");
+    }
+
+    #[test]
+    fn simulate_nothing() {
+        assert!(Simulation::new(1, 0).is_err(), "0 out of 0 is no estimate");
+    }
+
+    #[test]
+    fn rank_bags() {
+        let games : Vec<Game> = ["Game 1: 3 red, 1 blue", "Game 2: 4 red; 2 red, 1 blue"]
+            .iter().map(|l| str_to_game(l).unwrap()).collect();
+        let bags = [
+            Bag::parse("red=4,blue=4").unwrap(),
+            Bag::parse("red=3,blue=9").unwrap(),
+            Bag::parse("red=9,blue=1").unwrap(),
+        ];
+
+        let ranked : Vec<&str> = rank(&bags, &games, Draws::WithoutReplacement).unwrap().iter()
+            .map(|r| r.bag.name.as_str())
+            .collect();
+        assert_eq!(ranked, ["red=9,blue=1", "red=4,blue=4", "red=3,blue=9"],
+            "Mostly red games come from mostly red bags, and 3 red can't give 4");
+    }
+}
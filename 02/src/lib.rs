@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use aoc_common::{Answers, Input, Solver};
+use serde::{Deserialize, Serialize};
+
+mod bag;
+pub mod dump;
+pub mod generate;
+pub mod infer;
+mod parse;
+pub mod query;
+mod total;
+
+pub use bag::Bag;
+pub use parse::{str_to_game, str_to_game_into, ParseError};
+pub use total::Total;
+
+/// How many of each colour of cube. Colours are whatever the input says they
+/// are, and a colour that isn't mentioned isn't in the map at all.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Hand {
+    pub cubes: BTreeMap<String, i64>
+}
+
+impl Hand {
+    pub fn get(&self, colour: &str) -> i64 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+}
+
+/// The same `colour=count,colour=count` that `Bag::parse` reads.
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cubes : Vec<String> = self.cubes.iter()
+            .map(|(colour, n)| format!("{}={}", colour, n))
+            .collect();
+        write!(f, "{}", cubes.join(","))
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+    pub hands: Vec<Hand>,
+    pub id: i64
+}
+
+/// The same `Game N: a colour, b colour; ...` that the input is made of.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hands : Vec<String> = self.hands.iter()
+            .map(|h| h.cubes.iter()
+                .map(|(colour, n)| format!("{} {}", n, colour))
+                .collect::<Vec<_>>()
+                .join(", "))
+            .collect();
+        write!(f, "Game {}: {}", self.id, hands.join("; "))
+    }
+}
+
+/// Part 1 for each bag, and part 2, which doesn't need a bag.
+#[derive(Debug, PartialEq)]
+pub struct Tally<T = i64> {
+    pub possible_id_sums: Vec<T>,
+    pub powersum: T,
+}
+
+/// A colour in one hand of a game that there weren't enough of in the bag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Counts from 0, like `Game::hands`.
+    pub hand: usize,
+    pub colour: String,
+    pub shown: i64,
+    pub in_bag: i64,
+}
+
+impl Violation {
+    pub fn excess(&self) -> i64 {
+        self.shown - self.in_bag
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "hand {}: {} {}, {} more than the bag's {}",
+            self.hand + 1, self.shown, self.colour, self.excess(), self.in_bag)
+    }
+}
+
+#[derive(Debug)]
+pub struct Day02 {
+    /// Part 1 is answered for the first bag.
+    pub bags: Vec<Bag>,
+}
+
+impl Default for Day02 {
+    fn default() -> Self {
+        Day02 { bags: vec![Bag::puzzle()] }
+    }
+}
+
+impl Day02 {
+    pub fn tally(&self, input: Input) -> Result<Tally, aoc_common::Error> {
+        self.tally_with(input, |_, _, _| ())
+    }
+
+    /// The same as `tally`, but `impossible` is told about every game that
+    /// isn't possible with a bag, and why, as it goes. The totals are `T`,
+    /// which is how big they're allowed to get.
+    pub fn tally_with<T, F>(&self, input: Input, mut impossible: F) -> Result<Tally<T>, aoc_common::Error>
+        where T: Total,
+              F: FnMut(&Bag, &Game, Vec<Violation>)
+    {
+        let mut tally = Tally {
+            possible_id_sums: vec![T::default(); self.bags.len()],
+            powersum: T::default(),
+        };
+
+        // Everything in the one pass, so each game is only read once, and
+        // into the same Game every time
+        let mut g = Default::default();
+        let mut n = 0;
+        input.for_each_line(|line| {
+            n += 1;
+            str_to_game_into(line, &mut g).map_err(|e| e.at_line(n))?;
+
+            for (bag, total) in self.bags.iter().zip(&mut tally.possible_id_sums) {
+                let v = violations(&g, &bag.cubes);
+                if v.is_empty() {
+                    if !total.add(g.id) {
+                        return Err(overflow(&format!("the possible ID sum for {}", bag.name)));
+                    }
+                }
+                else {
+                    impossible(bag, &g, v);
+                }
+            }
+            if !tally.powersum.add_product(max_counts(&g.hands)) {
+                return Err(overflow("the sum of powers"));
+            }
+            Ok(())
+        })?;
+
+        Ok(tally)
+    }
+}
+
+fn overflow(what: &str) -> aoc_common::Error {
+    aoc_common::Error::Input(format!("{} is too big for 64 bits (--bigint has no limit)", what))
+}
+
+impl Solver for Day02 {
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn solve(&self, input: Input) -> Result<Answers, aoc_common::Error> {
+        let tally = self.tally(input)?;
+        Ok(Answers {
+            part1: tally.possible_id_sums.first().map(|t| (*t).into()),
+            part2: Some(tally.powersum.into()),
+            notes: vec![],
+        })
+    }
+}
+
+/// Each game in the input as it's read.
+pub fn games(input: Input) -> impl Iterator<Item = Result<Game, aoc_common::Error>> {
+    input.lines()
+        .enumerate()
+        .map(|(n, line)| Ok(str_to_game(&line?).map_err(|e| e.at_line(n + 1))?))
+}
+
+/// Every game in the input, for when one pass isn't enough.
+pub fn read_games(input: Input) -> Result<Vec<Game>, aoc_common::Error> {
+    games(input).collect()
+}
+
+pub fn is_game_possible(game: &Game, proto: &Hand) -> bool {
+    // Is any hand bigger than the prototype hand? That means it's not possible
+    !game.hands.iter()
+        .any(|h| is_any_field_bigger(h, proto))
+}
+
+/// Every colour in every hand that's more than `proto` has, in hand order.
+/// The game is possible if there aren't any.
+pub fn violations(game: &Game, proto: &Hand) -> Vec<Violation> {
+    let mut found = vec![];
+
+    for (i, h) in game.hands.iter().enumerate() {
+        for (colour, n) in &h.cubes {
+            let in_bag = proto.get(colour);
+            if *n > in_bag {
+                found.push(Violation {
+                    hand: i,
+                    colour: colour.clone(),
+                    shown: *n,
+                    in_bag,
+                });
+            }
+        }
+    }
+
+    found
+}
+
+fn is_any_field_bigger(lhs: &Hand, rhs: &Hand) -> bool {
+    // A colour rhs doesn't have at all is bigger in lhs if there are any
+    lhs.cubes.iter().any(|(colour, n)| *n > rhs.get(colour))
+}
+
+pub fn max_of_each_colour(hands: &[Hand]) -> Hand {
+    let mut hand : Hand = Default::default();
+
+    for h in hands {
+        for (colour, n) in &h.cubes {
+            let max = hand.cubes.entry(colour.clone()).or_insert(*n);
+            *max = std::cmp::max(*max, *n);
+        }
+    }
+
+    hand
+}
+
+/// `None` if it's too big for an `i64`.
+pub fn power(hand: &Hand) -> Option<i64> {
+    // Colours the game never showed aren't in the hand, so they don't count
+    hand.cubes.values().try_fold(1i64, |product, n| product.checked_mul(*n))
+}
+
+/// What `max_of_each_colour` would have, without making a new `Hand`.
+fn max_counts(hands: &[Hand]) -> impl Iterator<Item = i64> + '_ {
+    hands.iter().enumerate().flat_map(move |(i, h)| h.cubes.keys()
+        // Each colour counts once, from the first hand it's in
+        .filter(move |colour| !hands[..i].iter().any(|earlier| earlier.cubes.contains_key(*colour)))
+        .map(move |colour| hands[i..].iter().map(|h| h.get(colour)).max().unwrap_or(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    fn hand(cubes: &[(&str, i64)]) -> Hand {
+        Hand {
+            cubes: cubes.iter().map(|(c, n)| (c.to_string(), *n)).collect()
+        }
+    }
+
+    #[test]
+    fn any_colours() {
+        let g = str_to_game("Game 7: 3 blue, 4 red; 1 mauve, 2 green, 6 blue; 9 teal, 1 ochre")
+            .unwrap();
+
+        assert_eq!(g.id, 7);
+        assert_eq!(g.hands, [
+            hand(&[("blue", 3), ("red", 4)]),
+            hand(&[("mauve", 1), ("green", 2), ("blue", 6)]),
+            hand(&[("teal", 9), ("ochre", 1)]),
+        ]);
+
+        let max = max_of_each_colour(&g.hands);
+        assert_eq!(max, hand(&[("blue", 6), ("green", 2), ("mauve", 1),
+            ("ochre", 1), ("red", 4), ("teal", 9)]));
+        assert_eq!(power(&max), Some(6 * 2 * 4 * 9));
+        assert_eq!(max_counts(&g.hands).product::<i64>(), 6 * 2 * 4 * 9);
+    }
+
+    #[test]
+    fn several_bags() {
+        let day = Day02 {
+            bags: vec![Bag::puzzle(), Bag::parse("red=4,blue=3").unwrap()],
+        };
+        let input = "Game 1: 3 blue, 4 red\nGame 2: 5 blue\nGame 3: 1 green\n";
+
+        assert_eq!(day.tally(input.into()).unwrap(), Tally {
+            possible_id_sums: vec![1 + 2 + 3, 1],
+            powersum: 12 + 5 + 1,
+        });
+    }
+
+    #[test]
+    fn bad_line_stops_the_tally() {
+        let input = "Game 1: 3 blue\nGame 2: 3 blue, 2 blue\n";
+        let err = Day02::default().tally(input.into()).unwrap_err().to_string();
+
+        assert!(err.starts_with("bad input: line 2, column 19: blue is already in this hand"),
+            "Error says where: {}", err);
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let input = "Game 1: 4294967296 red, 4294967296 blue\n";
+
+        let err = Day02::default().tally(input.into()).unwrap_err().to_string();
+        assert_eq!(err, "bad input: the sum of powers is too big for 64 bits (--bigint has no limit)");
+
+        let big = Day02::default().tally_with::<BigInt, _>(input.into(), |_, _, _| ()).unwrap();
+        assert_eq!(big.powersum.to_string(), "18446744073709551616");
+
+        let input = "Game 9223372036854775807: 1 red\nGame 1: 1 red\n";
+        assert!(Day02::default().tally(input.into()).is_err(), "ID sums are checked too");
+        let big = Day02::default().tally_with::<BigInt, _>(input.into(), |_, _, _| ()).unwrap();
+        assert_eq!(big.possible_id_sums[0].to_string(), "9223372036854775808");
+    }
+
+    #[test]
+    fn possible_with_other_colours() {
+        let g = str_to_game("Game 1: 3 blue, 4 red; 2 teal").unwrap();
+
+        assert!(is_game_possible(&g, &hand(&[("blue", 3), ("red", 4), ("teal", 2)])));
+        assert!(!is_game_possible(&g, &hand(&[("blue", 3), ("red", 4)])),
+            "No teal in the bag at all");
+        assert!(!is_game_possible(&g, &hand(&[("blue", 2), ("red", 4), ("teal", 9)])),
+            "Not enough blue");
+    }
+
+    #[test]
+    fn every_violation() {
+        let g = str_to_game("Game 5: 13 red, 1 blue; 2 teal; 14 red, 20 blue").unwrap();
+        let v = violations(&g, &Bag::puzzle().cubes);
+
+        assert_eq!(v.iter().map(|v| (v.hand, v.colour.as_str(), v.excess())).collect::<Vec<_>>(),
+            [(0, "red", 1), (1, "teal", 2), (2, "blue", 6), (2, "red", 2)]);
+        assert_eq!(v[1].to_string(), "hand 2: 2 teal, 2 more than the bag's 0");
+        assert!(violations(&g, &hand(&[("red", 14), ("blue", 20), ("teal", 2)])).is_empty());
+    }
+
+    #[test]
+    fn tally_reports_impossible_games() {
+        let day = Day02 {
+            bags: vec![Bag::puzzle(), Bag::parse("red=4,blue=3").unwrap()],
+        };
+        let mut seen = vec![];
+        day.tally_with::<i64, _>("Game 1: 3 blue, 4 red\nGame 2: 5 blue\n".into(), |bag, g, v| {
+            seen.push((bag.name.clone(), g.id, v.len()));
+        }).unwrap();
+
+        assert_eq!(seen, [(String::from("red=4,blue=3"), 2, 1)]);
+    }
+}
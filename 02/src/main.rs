@@ -1,119 +1,222 @@
-use std::env;
-use std::fs::read_to_string;
-use regex::Regex;
+use std::io::{self, BufWriter, Write};
 
-#[derive(Debug)]
-struct Hand {
-    rgb: [ Option<i64>; 3 ]
-}
-
-#[derive(Debug)]
-struct Game {
-    hands: Vec<Hand>,
-    id: i64
-}
+use aoc_common::{Args, Error, Input};
+use aoc_2023_12_02::dump::{Format, Summary};
+use aoc_2023_12_02::generate::{parse_range, Generator};
+use aoc_2023_12_02::infer::{rank, Draws, Simulation};
+use aoc_2023_12_02::query::{best_bag, minimum_bag, Metric};
+use aoc_2023_12_02::{games, read_games, Bag, Day02, Total};
+use num_bigint::BigInt;
 
 fn main() {
-    let argv : Vec<_> = env::args().collect();
-    let proto_hand = Hand {
-        rgb: [ Some(12), Some(13), Some(14) ]
-    };
-
-    // The compiler told me to make this binding variable
-    let binding = read_to_string(&argv[1])
-        .unwrap();
-    let games = binding
-        .lines()
-        .map(|l| str_to_game(l));
-
-    let total = games.clone()
-        .filter(|g| is_game_possible(g, &proto_hand))
-        .map(|g| g.id)
-        .sum::<i64>();
-
-    let powersum = games.map(|g| max_of_each_colour(&g.hands))
-        .map(|h| power(&h))
-        .sum::<i64>();
-
-    println!("Part 1: {}", total);
-
-    println!("Part 2: {}", powersum);
-}
+    aoc_common::run(|| {
+        let mut args = Args::from_env();
+        let generating = args.command("generate");
+        let mut bags = args.values("--bag")?
+            .iter()
+            .map(|spec| Bag::parse(spec))
+            .collect::<Result<Vec<_>, _>>()?;
+        for path in args.values("--bag-file")? {
+            bags.extend(Bag::load(&path)?);
+        }
 
-fn is_game_possible(game: &Game, proto: &Hand) -> bool {
-    // Is any hand bigger than the prototype hand? That means it's not possible
-    return !game.hands.iter()
-        .any(|h| is_any_field_bigger(&h, proto));
-}
+        if generating {
+            return generate(args, bags);
+        }
 
-fn str_to_game(string: &str) -> Game {
-    // it doesn't really matter that the first split has the Game N: part
-    let hands = string.split(";").map(|h| str_to_hand(h));
-    let id = get_game_id(string);
+        let bigint = args.flag("--bigint");
 
-    return Game {
-        hands: hands.collect::<Vec<Hand>>(),
-        id: id
-    }
-}
+        if let Some(format) = args.value("--dump")? {
+            let format : Format = format.parse()?;
+            let input = args.input()?;
+            return if bigint { dump::<BigInt>(format, input) } else { dump::<i64>(format, input) };
+        }
 
-fn str_to_hand(string: &str) -> Hand {
-    let re_red = Regex::new(r"(\d+) red").unwrap();
-    let re_green = Regex::new(r"(\d+) green").unwrap();
-    let re_blue = Regex::new(r"(\d+) blue").unwrap();
-
-    Hand {
-        rgb: [
-            one_int_from_str(string, re_red),
-            one_int_from_str(string, re_green),
-            one_int_from_str(string, re_blue)
-        ]
-    }
-}
+        let report = args.flag("--report");
+        let min_bag = args.value("--min-bag")?;
+        let metric = args.value("--metric")?;
+        let budget = args.value("--best-bag")?;
+        let draws = args.value("--likelihood")?;
+        let trials = args.value("--simulate")?;
+        let seed = args.value("--seed")?;
 
-fn is_any_field_bigger(lhs: &Hand, rhs: &Hand) -> bool {
-    for n in 0..=2 {
-        if lhs.rgb[n].unwrap_or(0) > rhs.rgb[n].unwrap_or(0) {
-            return true;
+        if (trials.is_some() || seed.is_some()) && draws.is_none() {
+            return Err(Error::Usage(String::from("--simulate and --seed only go with --likelihood")));
+        }
+        if bigint && (draws.is_some() || min_bag.is_some() || budget.is_some()) {
+            return Err(Error::Usage(String::from("--bigint only goes with the answers or --dump")));
         }
-    }
 
-    return false;
-}
+        if let Some(draws) = draws {
+            let draws : Draws = draws.parse()?;
+            let number = |name: &str, value: Option<String>, default: u64| match value {
+                Some(v) => v.parse::<u64>()
+                    .map_err(|_| Error::Usage(format!("{}: {:?} is not a number", name, v))),
+                None => Ok(default),
+            };
+            let seed = number("--seed", seed, 1)?;
+            let mut sim = match trials {
+                Some(_) => Some(Simulation::new(seed, number("--simulate", trials, 0)? as usize)?),
+                None => None,
+            };
 
-fn one_int_from_str(string: &str, re: Regex) -> Option<i64> {
-    if let Some(res) = re.captures(string) {
-        let (_, [num]) = res.extract();
-        return Some(num.parse::<i64>().unwrap());
-    }
-    return None;
+            let games = read_games(args.input()?)?;
+            if bags.is_empty() {
+                bags.push(Bag::puzzle());
+            }
+
+            let mut rows = vec![vec![String::from("Bag"), String::from("Log-likelihood")]];
+            if sim.is_some() {
+                rows[0].push(String::from("Simulated"));
+            }
+            for r in rank(&bags, &games, draws)? {
+                let mut row = vec![r.bag.name.clone(), format!("{:.3}", r.log_likelihood)];
+                if let Some(sim) = &mut sim {
+                    let simulated : f64 = games.iter()
+                        .map(|g| sim.log_likelihood(g, &r.bag.cubes, draws))
+                        .sum::<Result<_, _>>()?;
+                    row.push(format!("{:.3}", simulated));
+                }
+                rows.push(row);
+            }
+
+            print!("{}", aoc_common::format_table(&rows));
+            return Ok(());
+        }
 
-}
+        if metric.is_some() && min_bag.is_none() {
+            return Err(Error::Usage(String::from("--metric only goes with --min-bag")));
+        }
+        if min_bag.is_some() && budget.is_some() {
+            return Err(Error::Usage(String::from("--min-bag and --best-bag are separate questions")));
+        }
+
+        if let Some(ids) = min_bag {
+            let ids = ids.split(',')
+                .map(|id| id.trim().parse::<i64>()
+                    .map_err(|_| Error::Usage(format!("--min-bag: {:?} is not a game ID", id))))
+                .collect::<Result<Vec<_>, _>>()?;
+            let metric : Metric = metric.as_deref().unwrap_or("total").parse()?;
+            let bag = minimum_bag(&read_games(args.input()?)?, &ids)?;
+
+            aoc_common::print_answer("Bag", &bag);
+            aoc_common::print_answer(match metric {
+                Metric::Total => "Total cubes",
+                Metric::Power => "Power",
+            }, metric.of(&bag).ok_or_else(|| Error::Input(
+                String::from("the bag is too big to measure in 64 bits")))?);
+            return Ok(());
+        }
 
-fn get_game_id(line: &str) -> i64 {
-    let re = Regex::new(r"Game (\d+)").unwrap();
-    return one_int_from_str(line, re).unwrap();
+        if let Some(budget) = budget {
+            let budget = budget.parse::<i64>().ok()
+                .filter(|n| *n >= 0)
+                .ok_or_else(|| Error::Usage(format!("--best-bag: {:?} is not a number of cubes", budget)))?;
+            let best = best_bag(&read_games(args.input()?)?, budget);
+            let ids : Vec<String> = best.ids.iter().map(|id| id.to_string()).collect();
+
+            aoc_common::print_answer("Bag", &best.cubes);
+            aoc_common::print_answer("Games", ids.join(","));
+            aoc_common::print_answer("Possible", best.ids.len());
+            return Ok(());
+        }
+
+        let input = args.input()?;
+
+        // With no bags given, it's just the puzzle
+        let table = !bags.is_empty();
+        let day = if bags.is_empty() { Day02::default() } else { Day02 { bags } };
 
+        if bigint {
+            show::<BigInt>(&day, input, report, table)
+        }
+        else {
+            show::<i64>(&day, input, report, table)
+        }
+    });
 }
 
-fn max_of_each_colour(hands: &Vec<Hand>) -> Hand {
-    let mut hand = Hand {
-        rgb: [None, None, None]
-    };
-
-    for h in hands {
-        for n in 0..=2 {
-            hand.rgb[n] = match (hand.rgb[n], h.rgb[n]) {
-                (x,        None)     => x,
-                (None,     Some(x))  => Some(x),
-                (Some(x1), Some(x2)) => Some(std::cmp::max(x1,x2))
-            };
+/// Answers in totals of `T`, with a table of bags if there's more than the
+/// puzzle's.
+fn show<T: Total>(day: &Day02, input: Input, report: bool, table: bool) -> Result<(), Error> {
+    let tally = day.tally_with::<T, _>(input, |bag, game, violations| {
+        if report {
+            println!("Game {} is impossible with {}", game.id, bag.name);
+            for v in violations {
+                println!("  {}", v);
+            }
         }
+    })?;
+
+    if table {
+        let mut rows = vec![vec![String::from("Bag"), String::from("Possible ID sum")]];
+        for (bag, total) in day.bags.iter().zip(&tally.possible_id_sums) {
+            rows.push(vec![bag.name.clone(), total.to_string()]);
+        }
+        print!("{}", aoc_common::format_table(&rows));
+    }
+    else {
+        aoc_common::print_answer("Part 1", &tally.possible_id_sums[0]);
     }
 
-    return hand;
+    aoc_common::print_answer("Part 2", tally.powersum);
+    Ok(())
+}
+
+/// Each game with its power as a `T`.
+fn dump<T: Total>(format: Format, input: Input) -> Result<(), Error> {
+    let stdout_err = |e| Error::Io(String::from("<stdout>"), e);
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    if let Some(header) = format.header() {
+        writeln!(out, "{}", header).map_err(stdout_err)?;
+    }
+    for game in games(input) {
+        writeln!(out, "{}", Summary::<T>::new(&game?)?.render(format)).map_err(stdout_err)?;
+    }
+    out.flush().map_err(stdout_err)
 }
 
-fn power(hand: &Hand) -> i64 {
-    return hand.rgb.iter().map(|x| x.unwrap_or(1)).fold(1, |acc,x| acc * x);
+/// `generate [--games N] [--hands N|MIN-MAX] [--colours a,b,c]
+/// [--counts uniform:LOW-HIGH|geometric:MEAN] [--possible FRACTION] [--seed N]`
+///
+/// `--possible` is for the first `--bag`, or the puzzle's.
+fn generate(mut args: Args, bags: Vec<Bag>) -> Result<(), Error> {
+    let mut generator = Generator::default();
+
+    if let Some(games) = args.value("--games")? {
+        generator.games = games.parse()
+            .map_err(|_| Error::Usage(format!("--games: {:?} is not a number", games)))?;
+    }
+    if let Some(hands) = args.value("--hands")? {
+        generator.hands = parse_range(&hands)
+            .ok_or_else(|| Error::Usage(format!("--hands: {:?} should be N or MIN-MAX", hands)))?;
+    }
+    if let Some(colours) = args.value("--colours")? {
+        generator.colours = colours.split(',').map(|c| c.trim().to_string()).collect();
+    }
+    if let Some(counts) = args.value("--counts")? {
+        generator.counts = counts.parse()?;
+    }
+    if let Some(fraction) = args.value("--possible")? {
+        let fraction = fraction.parse()
+            .map_err(|_| Error::Usage(format!("--possible: {:?} is not a fraction", fraction)))?;
+        let bag = bags.into_iter().next().unwrap_or_else(Bag::puzzle);
+        generator.possible = Some((bag.cubes, fraction));
+    }
+    if let Some(seed) = args.value("--seed")? {
+        generator.seed = seed.parse()
+            .map_err(|_| Error::Usage(format!("--seed: {:?} is not a number", seed)))?;
+    }
+    if let Some(arg) = args.input_path()? {
+        return Err(Error::Usage(format!("generate doesn't read input, but got {}", arg)));
+    }
+    generator.check()?;
+
+    // There could be a lot of them
+    let mut out = BufWriter::new(io::stdout().lock());
+    for game in generator.games() {
+        writeln!(out, "{}", game).map_err(|e| Error::Io(String::from("<stdout>"), e))?;
+    }
+    out.flush().map_err(|e| Error::Io(String::from("<stdout>"), e))
 }
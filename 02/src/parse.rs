@@ -0,0 +1,282 @@
+//! A parser for game lines, which are:
+//!
+//! ```text
+//! line   = "Game" id ":" hand { ";" hand }
+//! hand   = cubes { "," cubes }
+//! cubes  = count colour
+//! ```
+//!
+//! `id` and `count` are whole numbers, and a colour is a word made of letters.
+//! Spaces are allowed between any of these, and needed between the count and
+//! the colour. Anything else is an error, and so is a colour that's in a hand
+//! twice.
+
+use std::fmt;
+
+use crate::{Game, Hand};
+
+/// Where a line went wrong. The column counts characters from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line_number: Option<usize>,
+    pub column: usize,
+    pub message: String,
+    pub line: String,
+}
+
+impl ParseError {
+    pub fn at_line(self, line_number: usize) -> Self {
+        ParseError {
+            line_number: Some(line_number),
+            ..self
+        }
+    }
+}
+
+/// The position, the message, then the line with a caret under the problem.
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(n) = self.line_number {
+            write!(f, "line {}, ", n)?;
+        }
+        writeln!(f, "column {}: {}", self.column, self.message)?;
+        writeln!(f, "    {}", self.line)?;
+        write!(f, "    {:>width$}", "^", width = self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for aoc_common::Error {
+    fn from(e: ParseError) -> Self {
+        aoc_common::Error::Input(e.to_string())
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    // Byte offset of the next thing to read
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line_number: None,
+            column: self.text[..pos].chars().count() + 1,
+            message: message.into(),
+            line: self.text.to_string(),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Skip spaces, and say whether there were any.
+    fn spaces(&mut self) -> bool {
+        let start = self.pos;
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+        self.pos != start
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn keyword(&mut self, word: &str) -> Result<(), ParseError> {
+        if !self.rest().starts_with(word) {
+            return Err(self.error(format!("expected {:?}", word)));
+        }
+        self.pos += word.len();
+        Ok(())
+    }
+
+    fn number(&mut self, what: &str) -> Result<i64, ParseError> {
+        let start = self.pos;
+        let digits = self.rest().len()
+            - self.rest().trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+        if digits == 0 {
+            return Err(self.error(format!("expected {}", what)));
+        }
+
+        self.pos += digits;
+        self.text[start..self.pos].parse::<i64>()
+            .map_err(|_| self.error_at(start, format!("{} is too big", what)))
+    }
+
+    fn colour(&mut self) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        let len = rest.len() - rest.trim_start_matches(char::is_alphabetic).len();
+
+        if len == 0 {
+            return Err(self.error("expected a colour"));
+        }
+
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Read a hand over the top of `hand`, so colours it already has don't
+    /// need allocating again.
+    fn hand_into(&mut self, hand: &mut Hand) -> Result<(), ParseError> {
+        // Anything still -1 at the end wasn't in this hand
+        hand.cubes.values_mut().for_each(|n| *n = -1);
+
+        loop {
+            self.spaces();
+            let count = self.number("a number of cubes")?;
+            if !self.spaces() {
+                return Err(self.error("expected a space before the colour"));
+            }
+
+            let start = self.pos;
+            let colour = self.colour()?;
+            match hand.cubes.get_mut(colour) {
+                Some(n) if *n >= 0 => return Err(self.error_at(start,
+                    format!("{} is already in this hand", colour))),
+                Some(n) => *n = count,
+                None => {
+                    hand.cubes.insert(colour.to_string(), count);
+                }
+            }
+
+            self.spaces();
+            if !self.eat(',') {
+                hand.cubes.retain(|_, n| *n >= 0);
+                return Ok(());
+            }
+        }
+    }
+
+    fn game_into(&mut self, game: &mut Game) -> Result<(), ParseError> {
+        self.spaces();
+        self.keyword("Game")?;
+        self.spaces();
+        game.id = self.number("a game ID")?;
+        self.spaces();
+        if !self.eat(':') {
+            return Err(self.error("expected ':' after the game ID"));
+        }
+
+        let mut hands = 0;
+        loop {
+            if hands == game.hands.len() {
+                game.hands.push(Default::default());
+            }
+            self.hand_into(&mut game.hands[hands])?;
+            hands += 1;
+
+            if !self.eat(';') {
+                break;
+            }
+        }
+        game.hands.truncate(hands);
+
+        if self.peek().is_some() {
+            return Err(self.error("expected ',' or ';' or the end of the line"));
+        }
+
+        Ok(())
+    }
+}
+
+pub fn str_to_game(string: &str) -> Result<Game, ParseError> {
+    let mut game = Default::default();
+    str_to_game_into(string, &mut game)?;
+    Ok(game)
+}
+
+/// The same as `str_to_game`, but into a `Game` that's already there. Read
+/// every line into the same one, and the hands and colours a line has in
+/// common with the one before don't need allocating again. If the line is
+/// bad, what's left in `game` is nonsense.
+pub fn str_to_game_into(string: &str, game: &mut Game) -> Result<(), ParseError> {
+    Parser { text: string, pos: 0 }.game_into(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(line: &str) -> (usize, String) {
+        let e = str_to_game(line).unwrap_err();
+        (e.column, e.message)
+    }
+
+    #[test]
+    fn parse_games() {
+        let g = str_to_game("Game 12: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+
+        assert_eq!(g.id, 12);
+        assert_eq!(g.hands.len(), 3);
+        assert_eq!(g.hands[1].get("blue"), 6);
+
+        let g = str_to_game("  Game 3 :3 blue ,4 red;1 red  ").unwrap();
+        assert_eq!((g.id, g.hands.len()), (3, 2), "Spaces are flexible");
+    }
+
+    #[test]
+    fn reject_bad_lines() {
+        assert_eq!(error("Gaem 1: 3 blue"), (1, String::from("expected \"Game\"")));
+        assert_eq!(error("Game : 3 blue"), (6, String::from("expected a game ID")));
+        assert_eq!(error("Game 1 3 blue"), (8, String::from("expected ':' after the game ID")));
+        assert_eq!(error("Game 1: blue"), (9, String::from("expected a number of cubes")));
+        assert_eq!(error("Game 1: 3blue"), (10, String::from("expected a space before the colour")));
+        assert_eq!(error("Game 1: 3 blue, 2 red, 1 blue"),
+            (26, String::from("blue is already in this hand")));
+        assert_eq!(error("Game 1: 3 blue; 4 red!"),
+            (22, String::from("expected ',' or ';' or the end of the line")));
+        assert_eq!(error("Game 1: 3 blue;"), (16, String::from("expected a number of cubes")),
+            "No empty hands");
+        assert_eq!(error("Game 1: 99999999999999999999 blue").1, "a number of cubes is too big");
+    }
+
+    #[test]
+    fn same_colour_in_different_hands() {
+        assert!(str_to_game("Game 1: 3 blue; 3 blue").is_ok());
+    }
+
+    #[test]
+    fn reuse_a_game() {
+        let mut g = Default::default();
+
+        for line in ["Game 1: 3 blue, 4 red; 1 red, 2 green", "Game 2: 5 teal", "Game 3: 1 red; 2 red"] {
+            str_to_game_into(line, &mut g).unwrap();
+            assert_eq!(g, str_to_game(line).unwrap(), "Nothing left over from before");
+        }
+
+        assert!(str_to_game_into("Game 4: 1 red, 2 red", &mut g).is_err(),
+            "Duplicates are still caught when the colour was already there");
+    }
+
+    #[test]
+    fn caret_diagnostic() {
+        let e = str_to_game("Game 7: 3 blue, x red").unwrap_err().at_line(4);
+
+        assert_eq!(e.to_string(), concat!(
+            "line 4, column 17: expected a number of cubes\n",
+            "    Game 7: 3 blue, x red\n",
+            "                    ^"));
+    }
+
+    #[test]
+    fn caret_counts_characters() {
+        let e = str_to_game("Game 1: 2 grün, 2 grün").unwrap_err();
+        assert_eq!(e.column, 19, "ü is one column, even though it's two bytes");
+    }
+}
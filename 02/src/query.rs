@@ -0,0 +1,213 @@
+//! Questions about bags that are the other way round from part 1: rather than
+//! which games a bag allows, which bag allows some games.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use aoc_common::Error;
+
+use crate::{is_any_field_bigger, max_of_each_colour, power, Game, Hand};
+
+/// How big a bag is.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Metric {
+    /// Every cube in the bag.
+    #[default]
+    Total,
+    /// The product of the colours, like part 2.
+    Power,
+}
+
+impl Metric {
+    /// `None` if it's too big for an `i64`.
+    pub fn of(&self, hand: &Hand) -> Option<i64> {
+        match self {
+            Metric::Total => hand.cubes.values().try_fold(0i64, |sum, n| sum.checked_add(*n)),
+            Metric::Power => power(hand),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "total" => Ok(Metric::Total),
+            "power" => Ok(Metric::Power),
+            _ => Err(Error::Usage(format!("--metric must be total or power, not {:?}", s))),
+        }
+    }
+}
+
+/// The smallest bag that makes every game in `ids` possible.
+///
+/// That's the most of each colour any of them shows, and since no colour can
+/// go any lower, it's the smallest by any metric that grows with the counts.
+pub fn minimum_bag(games: &[Game], ids: &[i64]) -> Result<Hand, Error> {
+    let mut hands = vec![];
+
+    for id in ids {
+        let game = games.iter().find(|g| g.id == *id)
+            .ok_or_else(|| Error::Usage(format!("there is no game {}", id)))?;
+        hands.extend(game.hands.iter().cloned());
+    }
+
+    Ok(max_of_each_colour(&hands))
+}
+
+/// A bag with at most `budget` cubes, and the games it makes possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BestBag {
+    pub cubes: Hand,
+    pub ids: Vec<i64>,
+}
+
+/// The bag of no more than `budget` cubes that makes the most games possible.
+/// If there's a tie, the one with fewer cubes wins, then the first found.
+pub fn best_bag(games: &[Game], budget: i64) -> BestBag {
+    let needs : Vec<Hand> = games.iter().map(|g| max_of_each_colour(&g.hands)).collect();
+    let colours : Vec<String> = needs.iter()
+        .flat_map(|h| h.cubes.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut search = Search {
+        needs: &needs,
+        colours: &colours,
+        budget,
+        best: (0, 0, Default::default()),
+    };
+    let alive : Vec<usize> = (0..needs.len()).collect();
+    search.colour(0, &alive, 0, &mut Default::default());
+
+    let cubes = search.best.2;
+    let ids = games.iter()
+        .filter(|g| !g.hands.iter().any(|h| is_any_field_bigger(h, &cubes)))
+        .map(|g| g.id)
+        .collect();
+
+    BestBag { cubes, ids }
+}
+
+// Branch and bound, picking how many of each colour goes in the bag in turn.
+// The only counts worth trying are ones some game needs, and once a game
+// needs more than a count it's dead for every bag below this one.
+struct Search<'a> {
+    needs: &'a [Hand],
+    colours: &'a [String],
+    budget: i64,
+    // Games possible, total cubes, and the bag
+    best: (usize, i64, Hand),
+}
+
+impl Search<'_> {
+    fn colour(&mut self, c: usize, alive: &[usize], spent: i64, bag: &mut Hand) {
+        // Even if every game still alive makes it, this can't beat the best
+        let (best_count, best_spent, _) = &self.best;
+        if alive.len() < *best_count || (alive.len() == *best_count && spent >= *best_spent) {
+            return;
+        }
+
+        let Some(colour) = self.colours.get(c)
+        else {
+            self.best = (alive.len(), spent, bag.clone());
+            return;
+        };
+
+        let counts : BTreeSet<i64> = alive.iter()
+            .map(|g| self.needs[*g].get(colour))
+            .chain([0])
+            .collect();
+
+        // Most first, so the best bags tend to turn up early
+        for n in counts.into_iter().rev() {
+            if n > self.budget - spent {
+                continue;
+            }
+
+            let still : Vec<usize> = alive.iter().copied()
+                .filter(|g| self.needs[*g].get(colour) <= n)
+                .collect();
+
+            if n > 0 {
+                bag.cubes.insert(colour.clone(), n);
+            }
+            self.colour(c + 1, &still, spent + n, bag);
+            bag.cubes.remove(colour);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_game;
+
+    fn games() -> Vec<Game> {
+        [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ].iter().map(|l| str_to_game(l).unwrap()).collect()
+    }
+
+    #[test]
+    fn minimum_bags() {
+        let games = games();
+        let bag = minimum_bag(&games, &[1, 5]).unwrap();
+
+        assert_eq!(bag.to_string(), "blue=6,green=3,red=6");
+        assert_eq!(Metric::Total.of(&bag), Some(15));
+        assert_eq!(Metric::Power.of(&bag), Some(108));
+        assert_eq!(minimum_bag(&games, &[3]).unwrap().to_string(), "blue=6,green=13,red=20");
+        assert!(minimum_bag(&games, &[6]).is_err(), "No game 6");
+    }
+
+    #[test]
+    fn best_bags() {
+        let games = games();
+
+        assert_eq!(best_bag(&games, 1000).ids, [1, 2, 3, 4, 5], "Enough for everything");
+        assert_eq!(best_bag(&games, 0).ids, Vec::<i64>::new(), "Nothing fits in no cubes");
+
+        // Games 1, 2 and 5 need 6 blue, 3 green, 6 red between them
+        let best = best_bag(&games, 15);
+        assert_eq!(best.ids, [1, 2, 5]);
+        assert_eq!(best.cubes.to_string(), "blue=6,green=3,red=6");
+
+        // One short, and it's two games at best, for as few cubes as possible
+        let best = best_bag(&games, 14);
+        assert_eq!(best.ids, [1, 2], "Ties with 2 and 5, but found first");
+        assert_eq!(best.cubes.to_string(), "blue=6,green=3,red=4");
+    }
+
+    #[test]
+    fn best_bag_matches_brute_force() {
+        let games = games();
+
+        for budget in 0..45 {
+            let mut most = 0;
+            let bags = (0..=20)
+                .flat_map(|r| (0..=13).map(move |g| (r, g)))
+                .flat_map(|(r, g)| (0..=15).map(move |b| (r, g, b)))
+                .filter(|(r, g, b)| r + g + b <= budget);
+
+            for (r, g, b) in bags {
+                let bag = Hand {
+                    cubes: [("red", r), ("green", g), ("blue", b)].iter()
+                        .map(|(c, n)| (c.to_string(), *n))
+                        .collect()
+                };
+                most = most.max(games.iter()
+                    .filter(|g| !g.hands.iter().any(|h| is_any_field_bigger(h, &bag)))
+                    .count());
+            }
+
+            assert_eq!(best_bag(&games, budget).ids.len(), most, "Budget {}", budget);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Adding up answers, either in an `i64` that says so rather than wrapping
+//! when it would overflow, or in a `BigInt` that never does.
+
+use std::fmt;
+
+use num_bigint::BigInt;
+
+pub trait Total: Default + Clone + PartialEq + fmt::Debug + fmt::Display {
+    /// Add `n`, or say it didn't fit and leave the total alone.
+    fn add(&mut self, n: i64) -> bool;
+
+    /// Add all the factors multiplied together, or say they didn't fit.
+    fn add_product(&mut self, factors: impl IntoIterator<Item = i64>) -> bool;
+}
+
+impl Total for i64 {
+    fn add(&mut self, n: i64) -> bool {
+        match self.checked_add(n) {
+            Some(sum) => {
+                *self = sum;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn add_product(&mut self, factors: impl IntoIterator<Item = i64>) -> bool {
+        factors.into_iter()
+            .try_fold(1i64, |product, n| product.checked_mul(n))
+            .is_some_and(|product| self.add(product))
+    }
+}
+
+impl Total for BigInt {
+    fn add(&mut self, n: i64) -> bool {
+        *self += n;
+        true
+    }
+
+    fn add_product(&mut self, factors: impl IntoIterator<Item = i64>) -> bool {
+        *self += factors.into_iter().map(BigInt::from).product::<BigInt>();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked() {
+        let mut total = 0i64;
+
+        assert!(total.add(i64::MAX - 10));
+        assert!(total.add_product([2, 5]));
+        assert!(!total.add(1), "One more is too many");
+        assert!(!total.add_product([1 << 32, 1 << 32]), "The product is too big by itself");
+        assert_eq!(total, i64::MAX, "Nothing was added when it didn't fit");
+    }
+
+    #[test]
+    fn big() {
+        let mut total = BigInt::from(i64::MAX);
+
+        assert!(total.add(1));
+        assert!(total.add_product([1 << 32, 1 << 32]));
+        assert_eq!(total.to_string(), "27670116110564327424", "2^63 + 2^64");
+    }
+}
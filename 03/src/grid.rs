@@ -0,0 +1,191 @@
+//! The schematic as a grid of characters, so what's next to what is just a
+//! matter of looking.
+
+use aoc_common::{Error, Input};
+
+/// Row, then column, both counting characters from 0.
+pub type Pos = (usize, usize);
+
+/// A run of cells along one row. Both ends are included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub row: usize,
+    pub bounds: [usize; 2],
+}
+
+impl Span {
+    pub fn cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        (self.bounds[0]..=self.bounds[1]).map(|c| (self.row, c))
+    }
+
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row == self.row && (self.bounds[0]..=self.bounds[1]).contains(&col)
+    }
+}
+
+/// Rows don't have to be the same length; anything off the end of a row is
+/// just not there.
+///
+/// A grid can also be a window onto a longer one, by `push`ing rows on the
+/// end and `forget`ting the ones at the top. Rows keep their numbers, and
+/// the forgotten ones aren't there any more.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Grid {
+    // How many rows have been forgotten
+    first: usize,
+    rows: Vec<Vec<char>>,
+}
+
+impl Grid {
+    pub fn new<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Self {
+        Grid {
+            first: 0,
+            rows: lines.into_iter().map(|l| l.as_ref().chars().collect()).collect(),
+        }
+    }
+
+    pub fn read(input: Input) -> Result<Self, Error> {
+        Ok(Grid::new(input.lines().collect::<Result<Vec<_>, _>>()?))
+    }
+
+    /// Adds a row, from a line of text or another grid's row alike.
+    pub fn push(&mut self, row: impl IntoIterator<Item = char>) {
+        self.rows.push(row.into_iter().collect());
+    }
+
+    /// Forget all but the last `keep` rows.
+    pub fn forget(&mut self, keep: usize) {
+        let n = self.rows.len().saturating_sub(keep);
+        self.rows.drain(..n);
+        self.first += n;
+    }
+
+    /// One past the last row's number, which is the number of rows if none
+    /// have been forgotten.
+    pub fn rows(&self) -> usize {
+        self.first + self.rows.len()
+    }
+
+    pub fn row(&self, row: usize) -> &[char] {
+        row.checked_sub(self.first)
+            .and_then(|r| self.rows.get(r))
+            .map_or(&[], |r| r.as_slice())
+    }
+
+    pub fn get(&self, (row, col): Pos) -> Option<char> {
+        self.row(row).get(col).copied()
+    }
+
+    /// Every cell, a row at a time.
+    pub fn cells(&self) -> impl Iterator<Item = (Pos, char)> + '_ {
+        (self.first..self.rows()).flat_map(|r| self.row_cells(r))
+    }
+
+    pub fn row_cells(&self, row: usize) -> impl Iterator<Item = (Pos, char)> + '_ {
+        self.row(row).iter().enumerate().map(move |(c, ch)| ((row, c), *ch))
+    }
+
+    /// The up to eight cells around `pos`.
+    pub fn neighbours(&self, (row, col): Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.around(Span { row, bounds: [col, col] })
+    }
+
+    /// Every cell touching `span`, diagonals included, but not in it.
+    pub fn around(&self, span: Span) -> impl Iterator<Item = Pos> + '_ {
+        let [start, end] = span.bounds;
+
+        (span.row.saturating_sub(1)..=span.row + 1)
+            .flat_map(move |r| (start.saturating_sub(1)..=end + 1).map(move |c| (r, c)))
+            .filter(move |pos| !span.contains(*pos) && self.get(*pos).is_some())
+    }
+
+    /// Every run of digits, a row at a time.
+    pub fn number_spans(&self) -> Vec<Span> {
+        (self.first..self.rows()).flat_map(|r| self.row_number_spans(r)).collect()
+    }
+
+    pub fn row_number_spans(&self, r: usize) -> Vec<Span> {
+        let row = self.row(r);
+        let mut spans = vec![];
+        let mut start = None;
+
+        // One past the end, so a number at the end of the row finishes
+        for c in 0..=row.len() {
+            let digit = row.get(c).is_some_and(char::is_ascii_digit);
+            match (start, digit) {
+                (None, true) => start = Some(c),
+                (Some(s), false) => {
+                    spans.push(Span { row: r, bounds: [s, c - 1] });
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+
+        spans
+    }
+
+    pub fn text(&self, span: Span) -> String {
+        span.cells().filter_map(|pos| self.get(pos)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let g = Grid::new(["12.", "*", "..#"]);
+
+        assert_eq!(g.get((0, 1)), Some('2'));
+        assert_eq!(g.get((1, 1)), None, "Short rows are short");
+        assert_eq!(g.get((3, 0)), None, "Off the bottom");
+        assert_eq!(g.cells().filter(|(_, c)| *c != '.').count(), 4);
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        let g = Grid::new(["...", "...", "..."]);
+
+        assert_eq!(g.neighbours((1, 1)).count(), 8);
+        assert_eq!(g.neighbours((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 1)],
+            "Nothing off the edges");
+
+        let span = Span { row: 1, bounds: [0, 1] };
+        assert_eq!(g.around(span).collect::<Vec<_>>(),
+            [(0, 0), (0, 1), (0, 2), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn spans() {
+        let g = Grid::new(["012...678", "", ".5.44"]);
+        let spans = g.number_spans();
+
+        assert_eq!(spans, [
+            Span { row: 0, bounds: [0, 2] },
+            Span { row: 0, bounds: [6, 8] },
+            Span { row: 2, bounds: [1, 1] },
+            Span { row: 2, bounds: [3, 4] },
+        ]);
+        assert_eq!(g.text(spans[1]), "678");
+    }
+
+    #[test]
+    fn window() {
+        let mut g = Grid::default();
+        for line in ["1..", ".2.", "..3", "4.."] {
+            g.push(line.chars());
+            g.forget(2);
+        }
+
+        assert_eq!(g.rows(), 4, "Rows keep their numbers");
+        assert_eq!(g.get((1, 1)), None, "Forgotten");
+        assert_eq!(g.get((2, 2)), Some('3'));
+        assert_eq!(g.neighbours((2, 1)).count(), 5, "Nothing above row 2 any more");
+        assert_eq!(g.number_spans(), [
+            Span { row: 2, bounds: [2, 2] },
+            Span { row: 3, bounds: [0, 0] },
+        ]);
+    }
+}
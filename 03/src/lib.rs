@@ -0,0 +1,628 @@
+use std::collections::BTreeMap;
+
+use aoc_common::{Answers, Input, Solver};
+
+pub mod grid;
+pub mod render;
+pub mod rules;
+pub mod symbols;
+
+pub use grid::{Grid, Pos, Span};
+pub use rules::{GearRule, GearRules};
+pub use symbols::SymbolSet;
+
+/// Everything on the schematic, or on some rows of it.
+#[derive(Debug, Default)]
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>
+}
+
+#[derive(Debug, Clone)]
+struct Number( PlainOrPart, LiteralNumber );
+
+#[derive(Debug, Clone)]
+enum PlainOrPart {
+    PlainNumber,
+    PartNumber,
+}
+
+/// Columns here, and in `Symbol`, are characters, not bytes, so a line with
+/// a `·` or a `★` in it still lines up with the lines around it.
+#[derive(Debug, Clone, PartialEq)]
+struct LiteralNumber {
+    value: usize,
+    span: Span
+}
+
+/// A symbol and the numbers next to it, in reading order. If it might be a
+/// gear, whether it is is up to the `GearRules`.
+#[derive(Debug, Clone, PartialEq)]
+struct Symbol {
+    pos: Pos,
+    c: char,
+    gear: bool,
+    numbers: Vec<usize>,
+    // Where those numbers are in the whole schematic's `numbers`, counting
+    // from the first row
+    adjacent: Vec<usize>,
+}
+
+impl Schematic {
+    /// All of a grid at once.
+    fn new(grid: &Grid, set: &SymbolSet) -> Result<Self, aoc_common::Error> {
+        let mut schematic = Schematic::default();
+        let mut scanner = Scanner::new(set);
+
+        for r in 0..grid.rows() {
+            if let Some(row) = scanner.push(grid.row(r).iter().copied())? {
+                schematic.append(row);
+            }
+        }
+        if let Some(row) = scanner.finish() {
+            schematic.append(row);
+        }
+
+        Ok(schematic)
+    }
+
+    /// Reads `input` a line at a time, and hands each row of the schematic
+    /// to `f` as soon as it's finished.
+    fn scan<F>(input: Input, set: &SymbolSet, mut f: F) -> Result<(), aoc_common::Error>
+        where F: FnMut(Schematic) -> Result<(), aoc_common::Error>
+    {
+        let mut scanner = Scanner::new(set);
+
+        input.for_each_line(|line| match scanner.push(line.chars())? {
+            Some(row) => f(row),
+            None => Ok(()),
+        })?;
+
+        scanner.finish().map_or(Ok(()), f)
+    }
+
+    fn append(&mut self, mut rows: Schematic) {
+        self.numbers.append(&mut rows.numbers);
+        self.symbols.append(&mut rows.symbols);
+    }
+
+    fn sum_of_part_numbers(&self) -> usize {
+        self.numbers.iter().filter(|n| matches!(n.0, PlainOrPart::PartNumber))
+            .map(|n| n.1.value)
+            .sum::<usize>()
+    }
+
+    /// The symbols that are gears, with their ratios.
+    fn gears(&self, rules: &GearRules) -> Result<Vec<(&Symbol, usize)>, aoc_common::Error> {
+        let mut v = vec![];
+
+        for g in self.symbols.iter().filter(|s| s.gear) {
+            match rules.ratio(g.c, &g.numbers) {
+                Some(Some(ratio)) => v.push((g, ratio)),
+                Some(None) => return Err(aoc_common::Error::Input(format!(
+                    "line {}: the gear ratio is too big", g.pos.0 + 1))),
+                None => (),
+            }
+        }
+
+        Ok(v)
+    }
+
+    fn gear_ratios(&self, rules: &GearRules) -> Result<Vec<usize>, aoc_common::Error> {
+        Ok(self.gears(rules)?.into_iter().map(|(_, ratio)| ratio).collect())
+    }
+}
+
+/// Works the schematic out a row at a time. A row is finished once the row
+/// after it has been read, and after that only the row after needs it, so
+/// there are never more than three rows to hand.
+struct Scanner<'a> {
+    set: &'a SymbolSet,
+    window: Grid,
+    // The numbers in the window, with where they are in the whole schematic
+    numbers: Vec<(usize, LiteralNumber)>,
+    // How many numbers there have been
+    count: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(set: &'a SymbolSet) -> Self {
+        Scanner {
+            set,
+            window: Grid::default(),
+            numbers: vec![],
+            count: 0,
+        }
+    }
+
+    /// Adds the next row, which finishes the one before it, if there is one.
+    fn push(&mut self, cells: impl IntoIterator<Item = char>)
+        -> Result<Option<Schematic>, aoc_common::Error>
+    {
+        let row = self.window.rows();
+        self.window.push(cells);
+
+        // Checked once it's in the window, so there's only the one copy. The
+        // line is only put back together for the error.
+        let cells = self.window.row(row);
+        if let Some((col, c)) = cells.iter().enumerate().find(|(_, c)| !self.set.is_known(**c)) {
+            let line : String = cells.iter().collect();
+            return Err(aoc_common::Error::Input(format!(
+                "line {}, column {}: {:?} is not a digit, symbol or blank\n    {}\n    {:>width$}",
+                row + 1, col + 1, c, line, "^", width = col + 1)));
+        }
+
+        for span in self.window.row_number_spans(row) {
+            let text = self.window.text(span);
+            let value = text.parse::<usize>()
+                .map_err(|_| aoc_common::Error::Input(
+                    format!("line {}: {} is too big", row + 1, text)))?;
+
+            self.numbers.push((self.count, LiteralNumber { value, span }));
+            self.count += 1;
+        }
+
+        Ok(row.checked_sub(1).map(|r| self.finish_row(r)))
+    }
+
+    /// The last row, which has nothing after it to wait for.
+    fn finish(mut self) -> Option<Schematic> {
+        self.window.rows().checked_sub(1).map(|r| self.finish_row(r))
+    }
+
+    fn finish_row(&mut self, row: usize) -> Schematic {
+        let window = &self.window;
+        let set = self.set;
+        let mut schematic = Schematic::default();
+
+        for (_, n) in self.numbers.iter().filter(|(_, n)| n.span.row == row) {
+            let part = window.around(n.span)
+                .any(|pos| window.get(pos).is_some_and(|c| set.is_symbol(c)));
+
+            schematic.numbers.push(Number(
+                if part { PlainOrPart::PartNumber } else { PlainOrPart::PlainNumber },
+                n.clone()
+            ));
+        }
+
+        for (pos, c) in window.row_cells(row).filter(|(_, c)| set.is_symbol(*c)) {
+            // Keyed by which number it is, so a symbol next to two digits of
+            // the same number only counts it once
+            let adjacent : BTreeMap<usize, usize> = window.neighbours(pos)
+                .filter_map(|p| self.numbers.iter().find(|(_, n)| n.span.contains(p)))
+                .map(|(i, n)| (*i, n.value))
+                .collect();
+
+            schematic.symbols.push(Symbol {
+                pos,
+                c,
+                gear: set.is_gear(c),
+                numbers: adjacent.values().copied().collect(),
+                adjacent: adjacent.into_keys().collect(),
+            });
+        }
+
+        // The next row still needs this one, but not the one before
+        self.window.forget(2);
+        self.numbers.retain(|(_, n)| n.span.row >= row);
+
+        schematic
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Day03 {
+    pub rules: GearRules,
+    pub symbols: SymbolSet,
+}
+
+impl Day03 {
+    /// Every rule has to be for a symbol that might be a gear, or it could
+    /// never apply.
+    pub fn new(rules: GearRules, symbols: SymbolSet) -> Result<Self, aoc_common::Error> {
+        if let Some(rule) = rules.rules().iter().find(|r| !symbols.is_gear(r.symbol)) {
+            return Err(aoc_common::Error::Usage(
+                format!("there's a gear rule for {}, but it can't be a gear", rule.symbol)));
+        }
+        Ok(Day03 { rules, symbols })
+    }
+
+    /// From the command line's `--gear` rules, `--symbols`, `--blanks` and
+    /// `--gears`, filling in what's left out.
+    ///
+    /// Without any rules it's the puzzle's, as long as `*` is a symbol at
+    /// all, and without saying which symbols can be gears, it's whichever
+    /// symbols have rules.
+    pub fn from_options(rules: Vec<GearRule>, symbols: Option<&str>, blanks: &str,
+        gears: Option<&str>) -> Result<Self, aoc_common::Error>
+    {
+        let plain = SymbolSet::new(symbols, blanks, "")?;
+        let rules = if rules.is_empty() {
+            GearRules::new(GearRules::default().rules().iter()
+                .filter(|r| plain.is_symbol(r.symbol))
+                .copied()
+                .collect())?
+        }
+        else {
+            GearRules::new(rules)?
+        };
+
+        let gears = match gears {
+            Some(g) => g.to_string(),
+            None => rules.rules().iter()
+                .map(|r| r.symbol)
+                .filter(|c| plain.is_symbol(*c))
+                .collect(),
+        };
+
+        Day03::new(rules, SymbolSet::new(symbols, blanks, &gears)?)
+    }
+
+    /// The schematic, coloured in to show what's what.
+    pub fn render(&self, input: Input, format: render::Format) -> Result<String, aoc_common::Error> {
+        let grid = Grid::read(input)?;
+        let schematic = Schematic::new(&grid, &self.symbols)?;
+        render::render(&grid, &schematic, &self.rules, format)
+    }
+}
+
+impl Solver for Day03 {
+    fn day(&self) -> u32 {
+        3
+    }
+
+    /// A row at a time, so however long the input is, only a few rows of it
+    /// are in memory.
+    fn solve(&self, input: Input) -> Result<Answers, aoc_common::Error> {
+        let mut parts = 0;
+        let mut ratios = 0;
+
+        Schematic::scan(input, &self.symbols, |row| {
+            parts += row.sum_of_part_numbers();
+            ratios += row.gear_ratios(&self.rules)?.iter().sum::<usize>();
+            Ok(())
+        })?;
+
+        // Part 1 is the part numbers, part 2 the gear ratios
+        Ok(Answers::new(parts, ratios))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schematic(lines: &[&str]) -> Schematic {
+        Schematic::new(&Grid::new(lines), &SymbolSet::default()).unwrap()
+    }
+
+    #[test]
+    fn find_numbers() {
+
+        // will use char positions for digits.
+        // 678 is intentionally at the end of the string
+        let l = schematic(&["012...678"]);
+        assert_eq!(l.numbers.len(), 2, "Found 2 numbers");
+        assert_eq!(l.symbols.len(), 0, "Found 0 symbols");
+
+        assert!(matches!(l.numbers[0].0, PlainOrPart::PlainNumber),
+            "Numbers are plain by default");
+        assert!(matches!(l.numbers[1].0, PlainOrPart::PlainNumber),
+            "Numbers are plain by default");
+        assert_eq!(l.numbers[0].1.value, 12, "First number is 12 (really 012)");
+        assert_eq!(l.numbers[1].1.value, 678, "Second number is 678");
+        assert_eq!(l.numbers[0].1.span.bounds, [ 0, 2 ], "012 bound is from 0 to 2");
+        assert_eq!(l.numbers[1].1.span.bounds, [ 6, 8 ], "678 bound is from 6 to 8");
+    }
+
+    #[test]
+    fn find_symbols() {
+        let l = schematic(&["...+..!..-"]);
+
+        assert_eq!(l.numbers.len(), 0, "No numbers");
+        assert_eq!(l.symbols.len(), 3, "3 symbols");
+        assert_eq!(l.symbols.iter().map(|s| s.pos).collect::<Vec<_>>(),
+           [(0,3),(0,6),(0,9)], "Correct indices");
+    }
+
+    #[test]
+    fn part_numbers_from_the_next_line() {
+        let testline = schematic(&[
+            "1876...68.....143",
+            "....+.......&...*",
+        ]);
+
+        assert!(matches!(testline.numbers[0].0, PlainOrPart::PartNumber),
+            "First number now a PartNumber");
+        assert!(matches!(testline.numbers[1].0, PlainOrPart::PlainNumber),
+            "Second number still a PlainNumber");
+        assert!(matches!(testline.numbers[2].0, PlainOrPart::PartNumber),
+            "Third number is a PartNumber from below");
+
+        assert_eq!(testline.numbers[0].1.value, 1876, "Actual number is the same");
+    }
+
+    #[test]
+    fn part_numbers_from_the_line_before() {
+        let testline = schematic(&[
+            "....+.......&...*",
+            "1876...68.....143",
+        ]);
+
+        assert_eq!(testline.sum_of_part_numbers(), 1876 + 143,
+            "Symbols above count as much as symbols below");
+    }
+
+    #[test]
+    fn part_number_from_same_line() {
+        let testline = schematic(&["2233+..44."]);
+
+        assert_eq!(testline.numbers.len(), 2, "Found 2 numbers");
+        assert_eq!(testline.symbols.len(), 1, "Found 1 symbol");
+        assert!(matches!(testline.numbers[0].0, PlainOrPart::PartNumber),
+            "First number now a PartNumber");
+        assert!(matches!(testline.numbers[1].0, PlainOrPart::PlainNumber),
+            "Second number still a PlainNumber");
+    }
+
+    #[test]
+    fn multiple_symbols_for_same_number() {
+        let testline = schematic(&[
+            "..$1234....658.",
+            ".....^.^.......",
+        ]);
+
+        // The bug here was adding the number several times so this is the
+        // most relevant test here.
+        assert_eq!(testline.numbers.len(), 2, "Found 2 numbers");
+        assert_eq!(testline.symbols.len(), 3, "Found 3 symbols");
+        assert!(matches!(testline.numbers[0].0, PlainOrPart::PartNumber),
+            "First number now a PartNumber");
+        assert!(matches!(testline.numbers[1].0, PlainOrPart::PlainNumber),
+            "Second number still a PlainNumber");
+        assert_eq!(testline.sum_of_part_numbers(), 1234, "Counted once");
+    }
+
+    #[test]
+    fn gear_no_numbers() {
+        let testline = schematic(&[
+            ".+.....*......",
+            "..1234....658.",
+        ]);
+
+        assert_eq!(testline.symbols.len(), 2, "Found 2 symbols");
+        assert_eq!(testline.symbols[0].numbers, [1234], "+ is next to 1234");
+        assert!(testline.symbols[1].numbers.is_empty(), "Second symbol is a * but with no number");
+        assert!(testline.gear_ratios(&GearRules::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn gear_1_number() {
+        let testline = schematic(&[
+            ".+....*.......",
+            "..1234....658.",
+        ]);
+
+        assert_eq!(testline.symbols.len(), 2, "Found 2 symbols");
+        assert_eq!(testline.symbols[1].numbers, [1234], "* is next to 1234");
+        assert!(testline.gear_ratios(&GearRules::default()).unwrap().is_empty(),
+            "One number isn't enough");
+    }
+
+    #[test]
+    fn gear_2_numbers() {
+        let testline = schematic(&[
+            ".+....*.......",
+            "..1234.658....",
+        ]);
+
+        assert_eq!(testline.symbols.len(), 2, "Found 2 symbols");
+        assert_eq!(testline.symbols[1].numbers, [1234, 658], "Gear contains 1234 and 658");
+        assert_eq!(testline.gear_ratios(&GearRules::default()).unwrap(), [1234 * 658]);
+    }
+
+    #[test]
+    fn gear_3_numbers() {
+        let testline = schematic(&[
+            ".+....*94.....",
+            "..1234.658....",
+        ]);
+
+        assert_eq!(testline.symbols.len(), 2, "Found 2 symbols");
+        assert_eq!(testline.symbols[1].numbers, [94, 1234, 658], "Reading order");
+        assert!(testline.gear_ratios(&GearRules::default()).unwrap().is_empty(),
+            "Second symbol is not a gear after all");
+
+        let three = GearRules::new(vec!["*>=3:sum".parse().unwrap()]).unwrap();
+        assert_eq!(testline.gear_ratios(&three).unwrap(), [94 + 1234 + 658],
+            "Unless three is allowed");
+    }
+
+    #[test]
+    fn gear_ratios() {
+        let testline = schematic(&[
+            ".+....*94...-..2*...*",
+            "..1234.658.....123..1",
+        ]);
+
+        assert_eq!(testline.gear_ratios(&GearRules::default()).unwrap(), [2*123]);
+    }
+
+    fn testline_rows() -> [&'static str; 3] {
+        [
+            ".5.....7.",
+            "2#3...*..",
+            ".4...8.9.",
+        ]
+    }
+
+    #[test]
+    fn rules_per_symbol() {
+        let testline = schematic(&testline_rows());
+        let rules = GearRules::new(vec![
+            "#>=3:sum".parse().unwrap(),
+            "*=3:max".parse().unwrap(),
+        ]).unwrap();
+
+        assert_eq!(testline.gear_ratios(&rules).unwrap(), [9],
+            "# isn't a gear in the puzzle's symbols");
+
+        let set = SymbolSet::new(None, ".", "*#").unwrap();
+        let testline = Schematic::new(&Grid::new(testline_rows()), &set).unwrap();
+        assert_eq!(testline.gear_ratios(&rules).unwrap(), [5 + 2 + 3 + 4, 9]);
+    }
+
+    #[test]
+    fn gear_between_three_lines() {
+        let testline = schematic(&[
+            "..12..",
+            "...*..",
+            "....34",
+        ]);
+
+        assert_eq!(testline.gear_ratios(&GearRules::default()).unwrap(), [12*34],
+            "One number above and one below");
+    }
+
+    #[test]
+    fn scan_a_row_at_a_time() {
+        let mut rows = vec![];
+        Schematic::scan("..12..\n...*..\n....34\n".into(), &SymbolSet::default(), |row| {
+            rows.push(row);
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(rows.len(), 3, "One for each line");
+        assert_eq!(rows[0].sum_of_part_numbers(), 12, "Knows about the * below");
+        assert_eq!(rows[1].gear_ratios(&GearRules::default()).unwrap(), [12 * 34],
+            "The * is finished once the line below it is read");
+        assert_eq!(rows[2].sum_of_part_numbers(), 34);
+        assert_eq!(rows[1].symbols[0].adjacent, [0, 1], "Counting from the first row");
+    }
+
+    #[test]
+    fn other_symbols() {
+        let set = SymbolSet::new(Some("★§"), "·", "★").unwrap();
+        let testline = Schematic::new(&Grid::new(["·12★3·", "§·····", "4··5··"]), &set).unwrap();
+
+        assert_eq!(testline.sum_of_part_numbers(), 12 + 3 + 4, "5 isn't next to anything");
+        assert_eq!(testline.gear_ratios(&GearRules::new(vec!["★=2".parse().unwrap()]).unwrap())
+            .unwrap(), [12 * 3]);
+    }
+
+    #[test]
+    fn unknown_characters() {
+        let err = Schematic::new(&Grid::new(["12...", "..é.*"]), &SymbolSet::default())
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(err, concat!(
+            "bad input: line 2, column 3: 'é' is not a digit, symbol or blank\n",
+            "    ..é.*\n",
+            "      ^"));
+    }
+
+    fn wide_blanks(lines: &[&str]) -> Schematic {
+        // · and ★ are two and three bytes, . and # just one
+        let set = SymbolSet::new(Some("#*★"), ".·", "*★").unwrap();
+        Schematic::new(&Grid::new(lines), &set).unwrap()
+    }
+
+    #[test]
+    fn columns_are_characters() {
+        let testline = wide_blanks(&["··7", "..#"]);
+        assert_eq!(testline.numbers[0].1.span.bounds, [2, 2], "7 is the third character");
+        assert_eq!(testline.symbols[0].pos, (1, 2));
+        assert_eq!(testline.sum_of_part_numbers(), 7, "# is right below 7");
+
+        // In bytes, 7 is at 4 and so is #, but they're two characters apart
+        let testline = wide_blanks(&["··7", "....#"]);
+        assert_eq!(testline.sum_of_part_numbers(), 0, "# isn't next to 7");
+    }
+
+    #[test]
+    fn number_at_the_end_of_a_wide_line() {
+        for (below, part) in [("...#", true), ("....#", true), (".....#", false)] {
+            let testline = wide_blanks(&["··12", below]);
+
+            assert_eq!(testline.numbers[0].1.span.bounds, [2, 3]);
+            assert_eq!(testline.sum_of_part_numbers() == 12, part, "12 above {}", below);
+        }
+    }
+
+    #[test]
+    fn wide_symbols_and_gears() {
+        let testline = wide_blanks(&[
+            "★·12··",
+            "·★··★·",
+            "3····4",
+        ]);
+
+        assert_eq!(testline.symbols.iter().map(|s| s.pos).collect::<Vec<_>>(),
+            [(0, 0), (1, 1), (1, 4)]);
+        assert_eq!(testline.symbols[1].numbers, [12, 3], "The middle ★ touches 12 and 3");
+        assert_eq!(testline.symbols[2].numbers, [12, 4]);
+        assert_eq!(testline.gear_ratios(&GearRules::new(vec!["★=2".parse().unwrap()]).unwrap())
+            .unwrap(), [12 * 3, 12 * 4]);
+    }
+
+    #[test]
+    fn caret_under_the_right_character() {
+        let err = Schematic::new(&Grid::new(["★★x"]), &SymbolSet::new(Some("★"), ".", "").unwrap())
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.ends_with("column 3: 'x' is not a digit, symbol or blank\n    ★★x\n      ^"),
+            "{}", err);
+    }
+
+    #[test]
+    fn rules_must_be_for_gears() {
+        let rules = GearRules::new(vec!["#=2".parse().unwrap()]).unwrap();
+
+        assert!(Day03::new(rules.clone(), SymbolSet::default()).is_err());
+        assert!(Day03::new(rules, SymbolSet::new(None, ".", "#").unwrap()).is_ok());
+    }
+
+    #[test]
+    fn options() {
+        let rule = |r: &str| r.parse::<GearRule>().unwrap();
+
+        let day = Day03::from_options(vec![], None, ".", None).unwrap();
+        assert_eq!(day.rules, GearRules::default());
+        assert_eq!(day.symbols, SymbolSet::default(), "Nothing given is the puzzle");
+
+        let day = Day03::from_options(vec![], Some("#+$"), ".", None).unwrap();
+        assert!(day.rules.rules().is_empty(), "No * means no puzzle rule");
+        assert_eq!(day.symbols.gears().count(), 0);
+
+        let day = Day03::from_options(vec![rule("#>=3:sum")], None, ".", None).unwrap();
+        assert!(day.symbols.is_gear('#') && !day.symbols.is_gear('*'),
+            "Gears are the symbols with rules");
+
+        assert!(Day03::from_options(vec![rule("*=2")], Some("#"), ".", None).is_err(),
+            "A rule asked for has to be able to apply");
+        assert!(Day03::from_options(vec![], None, ".", Some("#")).is_err(),
+            "The puzzle rule is for *, which --gears left out");
+    }
+
+    #[test]
+    fn sum_of_part_numbers() {
+        let testline = schematic(&[
+            "1876...68...",
+            "......*..!.-",
+        ]);
+
+        assert_eq!(Schematic::sum_of_part_numbers(&testline), 68);
+    }
+
+    #[test]
+    fn both_parts_in_one_run() {
+        let input = concat!(
+            "467..114..\n", "...*......\n", "..35..633.\n", "......#...\n", "617*......\n",
+            ".....+.58.\n", "..592.....\n", "......755.\n", "...$.*....\n", ".664.598..\n");
+        let answers = Day03::default().solve(input.into()).unwrap();
+
+        assert_eq!(answers, Answers::new(4361usize, 467835usize));
+    }
+}
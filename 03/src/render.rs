@@ -0,0 +1,211 @@
+//! The schematic coloured in, for `--render`: part numbers in green, plain
+//! numbers dimmed, gears and their numbers in yellow, and other symbols in
+//! red. As HTML, hovering over a gear shows its ratio.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use aoc_common::Error;
+
+use crate::{GearRules, Grid, PlainOrPart, Schematic};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Terminal colours.
+    Ansi,
+    /// A page that stands by itself.
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Format::Ansi),
+            "html" => Ok(Format::Html),
+            _ => Err(Error::Usage(format!("--render must be ansi or html, not {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Blank,
+    Plain,
+    Part,
+    /// A number next to a gear, whatever else it is.
+    GearNumber,
+    /// The index into the list of gears.
+    Gear(usize),
+    Symbol,
+}
+
+impl Kind {
+    fn ansi(&self) -> Option<&'static str> {
+        match self {
+            Kind::Blank => None,
+            Kind::Plain => Some("2"),
+            Kind::Part => Some("32"),
+            Kind::GearNumber => Some("33"),
+            Kind::Gear(_) => Some("1;30;43"),
+            Kind::Symbol => Some("31"),
+        }
+    }
+
+    fn class(&self) -> Option<&'static str> {
+        match self {
+            Kind::Blank => None,
+            Kind::Plain => Some("plain"),
+            Kind::Part => Some("part"),
+            Kind::GearNumber => Some("gear-number"),
+            Kind::Gear(_) => Some("gear"),
+            Kind::Symbol => Some("symbol"),
+        }
+    }
+}
+
+const STYLE : &str = "\
+body { background: #10141c; color: #5c6370; }
+pre { font-size: 16px; line-height: 1.2; }
+.plain { opacity: 0.5; }
+.part { color: #98c379; }
+.gear-number { color: #e5c07b; }
+.gear { background: #e5c07b; color: #10141c; font-weight: bold; cursor: help; }
+.symbol { color: #e06c75; }
+";
+
+pub(crate) fn render(grid: &Grid, schematic: &Schematic, rules: &GearRules, format: Format)
+    -> Result<String, Error>
+{
+    let gears = schematic.gears(rules)?;
+    let mut kinds : Vec<Vec<Kind>> = (0..grid.rows())
+        .map(|r| vec![Kind::Blank; grid.row(r).len()])
+        .collect();
+
+    for n in &schematic.numbers {
+        let kind = match n.0 {
+            PlainOrPart::PlainNumber => Kind::Plain,
+            PlainOrPart::PartNumber => Kind::Part,
+        };
+        for (r, c) in n.1.span.cells() {
+            kinds[r][c] = kind;
+        }
+    }
+    for s in &schematic.symbols {
+        kinds[s.pos.0][s.pos.1] = Kind::Symbol;
+    }
+    // Gears last, so they win
+    for (i, (g, _)) in gears.iter().enumerate() {
+        kinds[g.pos.0][g.pos.1] = Kind::Gear(i);
+        for (r, c) in g.adjacent.iter().flat_map(|n| schematic.numbers[*n].1.span.cells()) {
+            kinds[r][c] = Kind::GearNumber;
+        }
+    }
+
+    let mut out = String::new();
+    if format == Format::Html {
+        out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str("<title>Gear ratios</title>\n");
+        write!(out, "<style>\n{}</style>\n", STYLE).unwrap();
+        out.push_str("</head>\n<body>\n<pre>\n");
+    }
+
+    for (r, row) in kinds.iter().enumerate() {
+        let cells = grid.row(r);
+        let mut c = 0;
+
+        // A run of cells the same kind at a time, except that each gear is
+        // its own run, for its tooltip
+        while c < row.len() {
+            let kind = row[c];
+            let end = (c + 1..row.len()).find(|e| row[*e] != kind).unwrap_or(row.len());
+            let text : String = cells[c..end].iter().collect();
+
+            match (format, kind) {
+                (Format::Ansi, _) => match kind.ansi() {
+                    Some(code) => write!(out, "\x1b[{}m{}\x1b[0m", code, text).unwrap(),
+                    None => out.push_str(&text),
+                },
+                (Format::Html, Kind::Gear(i)) => {
+                    let (g, ratio) = gears[i];
+                    let numbers : Vec<String> = g.numbers.iter().map(|n| n.to_string()).collect();
+                    write!(out, "<span class=\"gear\" title=\"ratio {} from {}\">{}</span>",
+                        ratio, numbers.join(", "), escape(&text)).unwrap();
+                }
+                (Format::Html, _) => match kind.class() {
+                    Some(class) => write!(out, "<span class=\"{}\">{}</span>",
+                        class, escape(&text)).unwrap(),
+                    None => out.push_str(&escape(&text)),
+                },
+            }
+
+            c = end;
+        }
+        out.push('\n');
+    }
+
+    if format == Format::Html {
+        out.push_str("</pre>\n</body>\n</html>\n");
+    }
+
+    Ok(out)
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SymbolSet;
+
+    fn draw(lines: &[&str], format: Format) -> String {
+        let grid = Grid::new(lines);
+        let schematic = Schematic::new(&grid, &SymbolSet::default()).unwrap();
+        render(&grid, &schematic, &GearRules::default(), format).unwrap()
+    }
+
+    #[test]
+    fn ansi() {
+        let out = draw(&["12*3..", "..#..9"], Format::Ansi);
+
+        assert_eq!(out, concat!(
+            "\x1b[33m12\x1b[0m\x1b[1;30;43m*\x1b[0m\x1b[33m3\x1b[0m..\n",
+            "..\x1b[31m#\x1b[0m..\x1b[2m9\x1b[0m\n"));
+    }
+
+    #[test]
+    fn html() {
+        let out = draw(&["12*3..", "<...9#"], Format::Html);
+
+        assert!(out.starts_with("<!DOCTYPE html>"));
+        assert!(out.ends_with("</html>\n"));
+        assert!(out.contains(concat!(
+            r#"<span class="gear-number">12</span><span class="gear" title="ratio 36 from 12, 3">*</span>"#,
+            r#"<span class="gear-number">3</span>.."#, "\n")));
+        assert!(out.contains(concat!(
+            r#"<span class="symbol">&lt;</span>...<span class="part">9</span>"#,
+            r#"<span class="symbol">#</span>"#)),
+            "Symbols are escaped: {}", out);
+    }
+
+    #[test]
+    fn each_gear_has_its_own_tooltip() {
+        let out = draw(&["2*3*4"], Format::Html);
+
+        assert!(out.contains(r#"title="ratio 6 from 2, 3""#));
+        assert!(out.contains(r#"title="ratio 12 from 3, 4""#));
+    }
+}
@@ -0,0 +1,184 @@
+//! What makes a symbol a gear, and what its ratio is.
+//!
+//! The puzzle's rule is that a `*` next to exactly two numbers is a gear, and
+//! its ratio is the two multiplied. A rule is written the same way on the
+//! command line:
+//!
+//! ```text
+//! *=2:product     a * next to exactly two numbers, multiplied
+//! #>=3:sum        a # next to three or more numbers, added up
+//! $=1:max         a $ next to one number, which is just that number
+//! ```
+//!
+//! The `:product` can be left off.
+
+use std::str::FromStr;
+
+use aoc_common::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Count {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Count {
+    fn allows(&self, n: usize) -> bool {
+        match *self {
+            Count::Exactly(k) => n == k,
+            Count::AtLeast(k) => n >= k,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Combine {
+    #[default]
+    Product,
+    Sum,
+    Max,
+}
+
+impl Combine {
+    /// `None` if it's too big for a `usize`.
+    fn apply(&self, numbers: &[usize]) -> Option<usize> {
+        match self {
+            Combine::Product => numbers.iter().try_fold(1usize, |acc, n| acc.checked_mul(*n)),
+            Combine::Sum => numbers.iter().try_fold(0usize, |acc, n| acc.checked_add(*n)),
+            Combine::Max => Some(numbers.iter().copied().max().unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GearRule {
+    pub symbol: char,
+    pub count: Count,
+    pub combine: Combine,
+}
+
+impl GearRule {
+    pub fn puzzle() -> Self {
+        GearRule {
+            symbol: '*',
+            count: Count::Exactly(2),
+            combine: Combine::Product,
+        }
+    }
+}
+
+impl FromStr for GearRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |msg: &str| Error::Usage(format!("gear rule {:?}: {}", s, msg));
+
+        let mut chars = s.chars();
+        let symbol = chars.next().ok_or_else(|| err("missing symbol"))?;
+        let rest = chars.as_str();
+
+        let (count, combine) = rest.split_once(':').unwrap_or((rest, "product"));
+        let count = if let Some(k) = count.strip_prefix(">=") {
+            Count::AtLeast(k.parse().map_err(|_| err("expected a number after >="))?)
+        }
+        else if let Some(k) = count.strip_prefix('=') {
+            Count::Exactly(k.parse().map_err(|_| err("expected a number after ="))?)
+        }
+        else {
+            return Err(err("expected =N or >=N after the symbol"));
+        };
+
+        let combine = match combine {
+            "product" => Combine::Product,
+            "sum" => Combine::Sum,
+            "max" => Combine::Max,
+            _ => return Err(err("combine with product, sum or max")),
+        };
+
+        Ok(GearRule { symbol, count, combine })
+    }
+}
+
+/// At most one rule for each symbol. Symbols without a rule are never gears.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearRules(Vec<GearRule>);
+
+impl Default for GearRules {
+    fn default() -> Self {
+        GearRules(vec![GearRule::puzzle()])
+    }
+}
+
+impl GearRules {
+    pub fn new(rules: Vec<GearRule>) -> Result<Self, Error> {
+        for (i, rule) in rules.iter().enumerate() {
+            if rules[..i].iter().any(|r| r.symbol == rule.symbol) {
+                return Err(Error::Usage(format!("more than one gear rule for {}", rule.symbol)));
+            }
+        }
+        Ok(GearRules(rules))
+    }
+
+    pub fn rules(&self) -> &[GearRule] {
+        &self.0
+    }
+
+    /// Whether `symbol` next to `numbers` is a gear. `Some(None)` means it's
+    /// a gear, but the ratio doesn't fit in a `usize`.
+    pub fn ratio(&self, symbol: char, numbers: &[usize]) -> Option<Option<usize>> {
+        self.0.iter()
+            .find(|r| r.symbol == symbol)
+            .filter(|r| r.count.allows(numbers.len()))
+            .map(|r| r.combine.apply(numbers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(specs: &[&str]) -> GearRules {
+        GearRules::new(specs.iter().map(|s| s.parse().unwrap()).collect()).unwrap()
+    }
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!("*=2".parse::<GearRule>().unwrap(), GearRule::puzzle());
+        assert_eq!("#>=3:sum".parse::<GearRule>().unwrap(), GearRule {
+            symbol: '#',
+            count: Count::AtLeast(3),
+            combine: Combine::Sum,
+        });
+
+        for bad in ["", "*", "*2", "*=two", "*>=3:mean", "*=:sum"] {
+            assert!(bad.parse::<GearRule>().is_err(), "{:?} is rejected", bad);
+        }
+        assert!(GearRules::new(vec![GearRule::puzzle(), "*>=3".parse().unwrap()]).is_err(),
+            "One rule per symbol");
+    }
+
+    #[test]
+    fn puzzle_rule() {
+        let r = GearRules::default();
+
+        assert_eq!(r.ratio('*', &[]), None);
+        assert_eq!(r.ratio('*', &[4]), None);
+        assert_eq!(r.ratio('*', &[4, 5]), Some(Some(20)));
+        assert_eq!(r.ratio('*', &[4, 5, 6]), None, "Three is too many");
+        assert_eq!(r.ratio('#', &[4, 5]), None, "Only * is a gear");
+    }
+
+    #[test]
+    fn other_rules() {
+        let r = rules(&["#>=3:sum", "$=1:max", "*=2:max"]);
+
+        assert_eq!(r.ratio('#', &[1, 2]), None);
+        assert_eq!(r.ratio('#', &[1, 2, 3]), Some(Some(6)));
+        assert_eq!(r.ratio('#', &[1, 2, 3, 4]), Some(Some(10)));
+        assert_eq!(r.ratio('$', &[7]), Some(Some(7)));
+        assert_eq!(r.ratio('*', &[7, 9]), Some(Some(9)));
+        assert_eq!(r.ratio('*', &[usize::MAX, 2]), Some(Some(usize::MAX)));
+        assert_eq!(rules(&["*=2"]).ratio('*', &[usize::MAX, 2]), Some(None),
+            "Too big to multiply");
+    }
+}
@@ -0,0 +1,111 @@
+//! Which characters can be in a schematic, and what they are.
+//!
+//! Digits are always numbers. Every other character has to be a symbol or a
+//! blank, and some symbols can be gears. Any character will do, not just
+//! ASCII, and one that isn't in the set at all is an error rather than
+//! quietly ignored.
+
+use std::collections::BTreeSet;
+
+use aoc_common::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolSet {
+    symbols: BTreeSet<char>,
+    blanks: BTreeSet<char>,
+    gears: BTreeSet<char>,
+}
+
+/// The puzzle's: `.` is blank, other ASCII punctuation is a symbol, and `*`
+/// might be a gear.
+impl Default for SymbolSet {
+    fn default() -> Self {
+        SymbolSet::new(None, ".", "*").unwrap()
+    }
+}
+
+impl SymbolSet {
+    /// Without `symbols`, it's all the ASCII punctuation that isn't blank.
+    pub fn new(symbols: Option<&str>, blanks: &str, gears: &str) -> Result<Self, Error> {
+        let blanks : BTreeSet<char> = blanks.chars().collect();
+        let symbols : BTreeSet<char> = match symbols {
+            Some(s) => s.chars().collect(),
+            None => (0..=127u8).map(char::from)
+                .filter(|c| c.is_ascii_punctuation() && !blanks.contains(c))
+                .collect(),
+        };
+        let gears : BTreeSet<char> = gears.chars().collect();
+
+        if let Some(c) = symbols.iter().chain(&blanks).find(|c| c.is_ascii_digit()) {
+            return Err(Error::Usage(format!("{} is a digit, so it can't be a symbol or a blank", c)));
+        }
+        if let Some(c) = symbols.intersection(&blanks).next() {
+            return Err(Error::Usage(format!("{} can't be both a symbol and a blank", c)));
+        }
+        if let Some(c) = gears.difference(&symbols).next() {
+            return Err(Error::Usage(format!("{} can't be a gear without being a symbol", c)));
+        }
+
+        Ok(SymbolSet { symbols, blanks, gears })
+    }
+
+    pub fn is_symbol(&self, c: char) -> bool {
+        self.symbols.contains(&c)
+    }
+
+    pub fn is_blank(&self, c: char) -> bool {
+        self.blanks.contains(&c)
+    }
+
+    pub fn is_gear(&self, c: char) -> bool {
+        self.gears.contains(&c)
+    }
+
+    pub fn is_known(&self, c: char) -> bool {
+        c.is_ascii_digit() || self.is_symbol(c) || self.is_blank(c)
+    }
+
+    pub fn gears(&self) -> impl Iterator<Item = char> + '_ {
+        self.gears.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzle_set() {
+        let set = SymbolSet::default();
+
+        assert!(set.is_blank('.'));
+        assert!(set.is_symbol('#') && set.is_symbol('*') && set.is_symbol('/'));
+        assert!(!set.is_symbol('.'), "Blank isn't a symbol");
+        assert!(set.is_gear('*') && !set.is_gear('#'));
+        assert!(set.is_known('7'));
+        assert!(!set.is_known('a') && !set.is_known(' ') && !set.is_known('é'));
+    }
+
+    #[test]
+    fn unicode() {
+        let set = SymbolSet::new(Some("★☆§"), "·", "★").unwrap();
+
+        assert!(set.is_symbol('★') && set.is_gear('★'));
+        assert!(set.is_symbol('§') && !set.is_gear('§'));
+        assert!(set.is_blank('·'));
+        assert!(!set.is_known('.') && !set.is_known('*'), "Only what was asked for");
+    }
+
+    #[test]
+    fn default_symbols_leave_out_blanks() {
+        let set = SymbolSet::new(None, ".-", "*").unwrap();
+        assert!(set.is_blank('-') && !set.is_symbol('-'));
+    }
+
+    #[test]
+    fn reject_bad_sets() {
+        assert!(SymbolSet::new(Some("#3"), ".", "").is_err(), "Digits are numbers");
+        assert!(SymbolSet::new(Some("#."), ".", "").is_err(), "Symbol and blank");
+        assert!(SymbolSet::new(Some("#"), ".", "*").is_err(), "Gear that isn't a symbol");
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use aoc_common::{Answers, Input, Solver};
+
+#[derive(Debug)]
+struct Card {
+    winning: Vec<usize>,
+    have: Vec<usize>
+}
+
+impl Card {
+    /// `Card N: winning numbers | numbers you have`, or what's wrong with it.
+    fn parse(s : &str) -> Result<Self, String> {
+        let (_, nums) = s.split_once(':')
+            .ok_or_else(|| format!("no ':' after the card number in {:?}", s))?;
+        let (wnums, hnums) = nums.split_once('|')
+            .ok_or_else(|| format!("no '|' between the numbers in {:?}", s))?;
+        let numbers = |nums: &str| nums.split_whitespace()
+            .map(|n| n.parse::<usize>().map_err(|_| format!("{:?} is not a number", n)))
+            .collect::<Result<Vec<_>, _>>();
+
+        Ok(Card {
+            winning: numbers(wnums)?,
+            have: numbers(hnums)?,
+        })
+    }
+
+    fn have_wins (&self) -> Vec<usize> {
+        self.have.iter().filter_map(|n| if self.winning.contains(n) {Some(*n)} else { None }) .collect()
+    }
+}
+
+pub struct Day04;
+
+impl Solver for Day04 {
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn solve(&self, input: Input) -> Result<Answers, aoc_common::Error> {
+        solve(input)
+    }
+}
+
+fn solve(input: Input) -> Result<Answers, aoc_common::Error> {
+    let mut pt1_score : usize = 0;
+    let mut pt2_score : usize = 0;
+    let mut multipliers : VecDeque<usize> = VecDeque::new();
+
+    for (i, input_line) in input.lines().enumerate() {
+        let c = Card::parse(&input_line?)
+            .map_err(|e| aoc_common::Error::Input(format!("line {}: {}", i + 1, e)))?;
+        let num_wins = c.have_wins().len();
+        let current_mult = multipliers.pop_front().unwrap_or(1);
+        pt2_score += current_mult;
+
+
+        if num_wins != 0 {
+            if multipliers.len() < num_wins {
+                multipliers.resize(num_wins, 1);
+            }
+
+            // Part 1: for every win on the card, this card is worth double;
+            // that's just 2^wins.
+            pt1_score += usize::pow(2, (c.have_wins().len() - 1).try_into().unwrap());
+
+            // Part 2: for every win on the card, you get 1 more of each of the
+            // next N cards. So if you already have X copies of this card, then
+            // all X of them will have N wins. So the next N cards will get X
+            // more copies. The total is how many cards you end up with.
+            for m in multipliers.iter_mut().take(num_wins) {
+                *m += current_mult;
+            }
+        }
+    }
+
+    Ok(Answers::new(pt1_score, pt2_score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_parts_in_one_run() {
+        let input = concat!(
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11\n");
+        let answers = Day04.solve(input.into()).unwrap();
+
+        assert_eq!(answers, Answers::new(13usize, 30usize));
+    }
+
+    #[test]
+    fn bad_lines() {
+        let err = Day04.solve("Card 1: 41 48 | 83 86\n\nCard 2: 1 | 1\n".into())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "bad input: line 2: no ':' after the card number in \"\"");
+
+        assert!(Day04.solve("Card 1: 41 48 83 86\n".into()).is_err(), "No |");
+        assert!(Day04.solve("Card 1: 41 4x | 83 86\n".into()).is_err(), "Not a number");
+    }
+}
@@ -0,0 +1,209 @@
+//! One binary for every day:
+//!
+//!     aoc run <day> [--part 1|2] [<input>]
+//!     aoc run all [--part 1|2]
+//!
+//! Without an input file, day N reads `NN/fullinput.txt`. An input of `-`
+//! means standard input.
+
+use std::env;
+
+use aoc_common::{Answers, Error, Input, Part, Solver};
+use aoc_2023_12_01::Day01;
+use aoc_2023_12_02::Day02;
+use aoc_2023_12_03::Day03;
+use aoc_2023_12_04::Day04;
+
+const USAGE: &str = "aoc run <day|all> [--part 1|2] [<input>]";
+
+fn solvers() -> Vec<Box<dyn Solver>> {
+    vec![
+        Box::new(Day01::default()),
+        Box::new(Day02::default()),
+        Box::new(Day03::default()),
+        Box::new(Day04),
+    ]
+}
+
+#[derive(Debug, PartialEq)]
+enum Days {
+    One(u32),
+    All,
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    days: Days,
+    part: Option<Part>,
+    input: Option<String>,
+}
+
+fn main() {
+    aoc_common::run(|| {
+        let args = parse_args(env::args().skip(1))?;
+        let solvers = solvers();
+
+        let chosen : Vec<&dyn Solver> = match args.days {
+            Days::All => solvers.iter().map(|s| s.as_ref()).collect(),
+            Days::One(day) => vec![
+                solvers.iter()
+                    .find(|s| s.day() == day)
+                    .ok_or_else(|| Error::Usage(format!("no solver for day {}", day)))?
+                    .as_ref()
+            ],
+        };
+
+        let mut rows = vec![];
+        for solver in chosen {
+            let path = args.input.clone()
+                .unwrap_or_else(|| default_input(solver.day()));
+            rows.push((solver.day(), solver.solve(Input::open(&path)?)?));
+        }
+
+        print!("{}", table(&rows, args.part));
+        for note in notes(&rows) {
+            eprintln!("{}", note);
+        }
+        Ok(())
+    });
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, Error> {
+    let usage = || Error::Usage(USAGE.to_string());
+
+    if args.next().as_deref() != Some("run") {
+        return Err(usage());
+    }
+
+    let days = match args.next().as_deref() {
+        Some("all") => Days::All,
+        Some(d) => Days::One(d.parse().map_err(|_| usage())?),
+        None => return Err(usage()),
+    };
+
+    let mut part = None;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        if arg == "--part" {
+            part = Some(args.next().ok_or_else(usage)?.parse()?);
+        }
+        else if let Some(p) = arg.strip_prefix("--part=") {
+            part = Some(p.parse()?);
+        }
+        else if input.is_none() && !arg.starts_with("--") {
+            input = Some(arg);
+        }
+        else {
+            return Err(usage());
+        }
+    }
+
+    if days == Days::All && input.is_some() {
+        return Err(Error::Usage(
+            String::from("an input file only makes sense for a single day")));
+    }
+
+    Ok(Args { days, part, input })
+}
+
+fn default_input(day: u32) -> String {
+    format!("{:02}/fullinput.txt", day)
+}
+
+/// Lay the answers out one day per row, with a column per part. Parts the
+/// solver doesn't answer are shown as `-`.
+fn table(rows: &[(u32, Answers)], only: Option<Part>) -> String {
+    let parts : Vec<Part> = match only {
+        Some(p) => vec![p],
+        None => Part::BOTH.to_vec(),
+    };
+
+    let mut cells : Vec<Vec<String>> = vec![];
+    cells.push(
+        std::iter::once(String::from("Day"))
+            .chain(parts.iter().map(|p| p.to_string()))
+            .collect()
+    );
+
+    for (day, answers) in rows {
+        cells.push(
+            std::iter::once(day.to_string())
+                .chain(parts.iter().map(|p| match answers.part(*p) {
+                    Some(a) => a.to_string(),
+                    None => String::from("-"),
+                }))
+                .collect()
+        );
+    }
+
+    aoc_common::format_table(&cells)
+}
+
+/// What the solvers had to say, with the day, since there's more than one.
+fn notes(rows: &[(u32, Answers)]) -> Vec<String> {
+    rows.iter()
+        .flat_map(|(day, answers)| answers.notes.iter()
+            .map(move |(part, note)| format!("Day {}, {}: {}", day, part, note)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<Args, Error> {
+        parse_args(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_single_day() {
+        let a = args("run 3 --part 2 03/smallinput.txt").unwrap();
+        assert_eq!(a, Args {
+            days: Days::One(3),
+            part: Some(Part::Two),
+            input: Some(String::from("03/smallinput.txt")),
+        });
+
+        let a = args("run 1").unwrap();
+        assert_eq!(a.input, None, "Input falls back to the default later");
+    }
+
+    #[test]
+    fn parse_rejects_nonsense() {
+        assert!(args("").is_err(), "Need a subcommand");
+        assert!(args("run").is_err(), "Need a day");
+        assert!(args("run x").is_err(), "Day must be a number");
+        assert!(args("run 1 --part 3").is_err(), "Only 2 parts");
+        assert!(args("run all some.txt").is_err(),
+            "One input can't be for every day");
+    }
+
+    #[test]
+    fn table_lines_up() {
+        let rows = vec![
+            (1, Answers { part1: None, part2: Some(281i64.into()), notes: vec![] }),
+            (2, Answers::new(8i64, 2286i64)),
+        ];
+
+        assert_eq!(table(&rows, None),
+            "Day  Part 1  Part 2\n  \
+               1       -     281\n  \
+               2       8    2286\n");
+        assert_eq!(table(&rows, Some(Part::One)),
+            "Day  Part 1\n  1       -\n  2       8\n");
+    }
+
+    #[test]
+    fn one_part_cannot_sink_the_other() {
+        // Part 2's example for day 1 has lines without a plain digit, which
+        // part 1 can't make sense of
+        let day1 = solvers().into_iter().find(|s| s.day() == 1).unwrap();
+        let answers = day1.solve(Input::open("../01/smallinput.txt").unwrap()).unwrap();
+
+        assert_eq!(answers.part1, None);
+        assert_eq!(answers.part2, Some(281i64.into()));
+        assert_eq!(notes(&[(1, answers)]), [r#"Day 1, Part 1: line 2: no digit in "eightwothree""#],
+            "Why not, and for which day");
+    }
+}
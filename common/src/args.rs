@@ -0,0 +1,147 @@
+//! Just enough command line handling for the day binaries.
+//!
+//! Each day pulls out the options it knows about, then whatever is left must
+//! be the input path, if anything. Anything else that looks like an option
+//! is an error.
+
+use std::env;
+
+use crate::{Error, Input};
+
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    /// The process arguments, without the program name.
+    pub fn from_env() -> Self {
+        Args::new(env::args().skip(1))
+    }
+
+    pub fn new(args: impl IntoIterator<Item = String>) -> Self {
+        Args {
+            args: args.into_iter().collect(),
+        }
+    }
+
+    /// Remove `name` if it's the first argument, and say whether it was.
+    pub fn command(&mut self, name: &str) -> bool {
+        if self.args.first().map(String::as_str) != Some(name) {
+            return false;
+        }
+        self.args.remove(0);
+        true
+    }
+
+    /// Remove `name` if it was given, and say whether it was.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let before = self.args.len();
+        self.args.retain(|a| a != name);
+        self.args.len() != before
+    }
+
+    /// Remove `name value` or `name=value` and return the value. If it's given
+    /// more than once, the last one wins.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>, Error> {
+        Ok(self.values(name)?.pop())
+    }
+
+    /// Remove every `name value` or `name=value` and return the values in
+    /// the order they were given.
+    pub fn values(&mut self, name: &str) -> Result<Vec<String>, Error> {
+        let prefix = format!("{}=", name);
+        let mut found = vec![];
+        let mut rest = vec![];
+        let mut args = self.args.drain(..);
+
+        while let Some(arg) = args.next() {
+            if arg == name {
+                match args.next() {
+                    Some(v) => found.push(v),
+                    None => return Err(Error::Usage(format!("{} needs a value", name))),
+                }
+            }
+            else if let Some(v) = arg.strip_prefix(&prefix) {
+                found.push(v.to_string());
+            }
+            else {
+                rest.push(arg);
+            }
+        }
+
+        drop(args);
+        self.args = rest;
+        Ok(found)
+    }
+
+    /// Whatever is left once the options have been taken out, which must be
+    /// at most one path.
+    pub fn input_path(self) -> Result<Option<String>, Error> {
+        let mut args = self.args.into_iter();
+
+        match (args.next(), args.next()) {
+            (Some(arg), _) if arg.starts_with("--") =>
+                Err(Error::Usage(format!("unknown option {}", arg))),
+            (_, Some(arg)) =>
+                Err(Error::Usage(format!("unexpected argument {}", arg))),
+            (path, None) => Ok(path),
+        }
+    }
+
+    /// Open the input path, or read standard input if it's `-` or missing.
+    pub fn input(self) -> Result<Input, Error> {
+        match self.input_path()? {
+            Some(path) => Input::open(&path),
+            None => Ok(Input::stdin()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::new(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn options_anywhere() {
+        let mut a = args("--vocab fr.txt input.txt --explain");
+
+        assert!(a.flag("--explain"), "Flag was given");
+        assert!(!a.flag("--quiet"), "Flag was not given");
+        assert_eq!(a.value("--vocab").unwrap().as_deref(), Some("fr.txt"));
+        assert_eq!(a.input_path().unwrap().as_deref(), Some("input.txt"));
+    }
+
+    #[test]
+    fn commands_come_first() {
+        let mut a = args("generate --games 5");
+        assert!(!a.command("run"), "Not that command");
+        assert!(a.command("generate"));
+        assert!(!a.command("generate"), "Only once");
+
+        assert!(!args("input.txt generate").command("generate"), "Only at the start");
+    }
+
+    #[test]
+    fn repeated_values() {
+        let mut a = args("--bag a --bag=b input.txt --bag c");
+
+        assert_eq!(a.values("--bag").unwrap(), ["a", "b", "c"]);
+        assert_eq!(a.input_path().unwrap().as_deref(), Some("input.txt"));
+    }
+
+    #[test]
+    fn leftovers_are_errors() {
+        assert_eq!(args("").input_path().unwrap(), None, "Input is optional");
+        assert_eq!(args("-").input_path().unwrap().as_deref(), Some("-"),
+            "- is a path, not an option");
+        assert!(args("a.txt b.txt").input_path().is_err(), "Only one input");
+        assert!(args("--what a.txt").input_path().is_err(), "Unknown option");
+        assert!(args("a.txt --bag").values("--bag").is_err(),
+            "Option without its value");
+    }
+}
@@ -0,0 +1,124 @@
+//! Puzzle input, read a line at a time so it never has to fit in memory.
+
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, Cursor};
+
+use crate::Error;
+
+pub struct Input {
+    name: String,
+    reader: Box<dyn BufRead>,
+}
+
+impl Input {
+    /// Open a file, or standard input if the path is `-`.
+    pub fn open(path: &str) -> Result<Self, Error> {
+        if path == "-" {
+            return Ok(Input::stdin());
+        }
+
+        let file = File::open(path)
+            .map_err(|e| Error::Io(path.to_string(), e))?;
+
+        Ok(Input {
+            name: path.to_string(),
+            reader: Box::new(BufReader::new(file)),
+        })
+    }
+
+    pub fn stdin() -> Self {
+        Input {
+            name: String::from("<stdin>"),
+            reader: Box::new(io::stdin().lock()),
+        }
+    }
+
+    /// Each line without its line ending. A read error ends the input, after
+    /// being passed on.
+    pub fn lines(self) -> impl Iterator<Item = Result<String, Error>> {
+        let name = self.name;
+        let mut failed = false;
+
+        self.reader.lines().map_while(move |line| {
+            if failed {
+                return None;
+            }
+            failed = line.is_err();
+            Some(line.map_err(|e| Error::Io(name.clone(), e)))
+        })
+    }
+
+    /// Each line without its line ending, read into the same buffer every
+    /// time, for when allocating a `String` per line is too much. Stops at
+    /// the first error, from reading or from `f`.
+    pub fn for_each_line<F>(mut self, mut f: F) -> Result<(), Error>
+        where F: FnMut(&str) -> Result<(), Error>
+    {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            let read = self.reader.read_line(&mut line)
+                .map_err(|e| Error::Io(self.name.clone(), e))?;
+            if read == 0 {
+                return Ok(());
+            }
+
+            let text = match line.strip_suffix('\n') {
+                Some(l) => l.strip_suffix('\r').unwrap_or(l),
+                None => &line,
+            };
+            f(text)?;
+        }
+    }
+}
+
+/// Input that's already in memory, which is mostly useful for tests.
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Input {
+            name: String::from("<text>"),
+            reader: Box::new(Cursor::new(text.as_bytes().to_vec())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_from_text() {
+        let lines : Vec<String> = Input::from("one\ntwo\r\n\nthree")
+            .lines()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(lines, ["one", "two", "", "three"]);
+    }
+
+    #[test]
+    fn each_line_in_one_buffer() {
+        let mut lines = vec![];
+        Input::from("one\ntwo\r\n\nthree")
+            .for_each_line(|l| {
+                lines.push(l.to_string());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(lines, ["one", "two", "", "three"], "Same lines as lines()");
+
+        let stopped = Input::from("a\nb\nc").for_each_line(|l| match l {
+            "b" => Err(Error::Input(String::from("no b"))),
+            _ => Ok(()),
+        });
+        assert!(matches!(stopped, Err(Error::Input(_))), "Errors stop it");
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(matches!(Input::open("/no/such/file"), Err(Error::Io(..))));
+    }
+}
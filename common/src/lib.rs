@@ -0,0 +1,205 @@
+//! Plumbing shared by every day's binary: finding and reading the puzzle
+//! input, printing answers, and turning failures into a message and an exit
+//! code instead of a panic.
+
+use std::env;
+use std::fmt;
+use std::fmt::Display;
+use std::fs::read_to_string;
+use std::io;
+use std::process;
+
+mod args;
+mod input;
+mod rng;
+
+pub use args::Args;
+pub use input::Input;
+pub use rng::Rng;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The command line was wrong; the string is what to tell the user.
+    Usage(String),
+    /// The input file couldn't be read.
+    Io(String, io::Error),
+    /// The input was read but didn't make sense.
+    Input(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => write!(f, "usage: {}", msg),
+            Error::Io(path, e) => write!(f, "{}: {}", path, e),
+            Error::Input(msg) => write!(f, "bad input: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A single answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Number(i128),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Number(n as i128)
+    }
+}
+
+/// Both answers for a day. A part is `None` if the solver doesn't do it (yet),
+/// or couldn't for this input, in which case there's a note saying why.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    /// For whoever's printing the answers to pass on.
+    pub notes: Vec<(Part, String)>,
+}
+
+impl Answers {
+    pub fn new(part1: impl Into<Answer>, part2: impl Into<Answer>) -> Self {
+        Answers {
+            part1: Some(part1.into()),
+            part2: Some(part2.into()),
+            notes: vec![],
+        }
+    }
+
+    pub fn part(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "Part 1"),
+            Part::Two => write!(f, "Part 2"),
+        }
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(Error::Usage(format!("no such part {:?}; use 1 or 2", s))),
+        }
+    }
+}
+
+/// Everything a day has to provide for the `aoc` runner to drive it.
+pub trait Solver {
+    /// Day of the month, 1 to 25.
+    fn day(&self) -> u32;
+
+    fn solve(&self, input: Input) -> Result<Answers, Error>;
+}
+
+/// Read a whole small file, like a config file. Puzzle input should be read
+/// as an [`Input`] instead.
+pub fn load_input(path: &str) -> Result<String, Error> {
+    read_to_string(path).map_err(|e| Error::Io(path.to_string(), e))
+}
+
+pub fn print_answer(label: &str, value: impl Display) {
+    println!("{}: {}", label, value);
+}
+
+/// Lay out rows of cells in right-aligned columns, each as wide as its widest
+/// cell, with a line ending after every row.
+pub fn format_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths : Vec<usize> = (0..columns)
+        .map(|col| rows.iter()
+            .filter_map(|r| r.get(col))
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(0))
+        .collect();
+
+    let mut out = String::new();
+    for row in rows {
+        let line = row.iter().zip(&widths)
+            .map(|(cell, w)| format!("{:>w$}", cell, w = w))
+            .collect::<Vec<_>>()
+            .join("  ");
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Print each part that has an answer, in the same style for every day, and
+/// any notes on stderr.
+pub fn print_answers(answers: &Answers) {
+    for part in Part::BOTH {
+        if let Some(answer) = answers.part(part) {
+            print_answer(&part.to_string(), answer);
+        }
+    }
+    for (part, note) in &answers.notes {
+        eprintln!("{}: {}", part, note);
+    }
+}
+
+/// The whole of a day's `main`: solve the file named on the command line, or
+/// standard input, and print both answers.
+pub fn run_solver(solver: &dyn Solver) {
+    run(|| {
+        let input = Args::from_env().input()?;
+        print_answers(&solver.solve(input)?);
+        Ok(())
+    });
+}
+
+/// Run a day's body, and if it fails, say why on stderr and exit non-zero.
+pub fn run<F>(body: F)
+where
+    F: FnOnce() -> Result<(), Error>,
+{
+    if let Err(e) = body() {
+        eprintln!("{}: {}", program_name(), e);
+        process::exit(1);
+    }
+}
+
+fn program_name() -> String {
+    env::args().next().unwrap_or_else(|| String::from("aoc"))
+}